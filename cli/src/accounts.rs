@@ -0,0 +1,161 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use anchor_lang::{ AccountDeserialize, Discriminator };
+use anyhow::{ anyhow, Context, Result };
+use solana_account_decoder::UiAccountEncoding;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::config::{ RpcAccountInfoConfig, RpcProgramAccountsConfig };
+use solana_rpc_client_api::filter::{ Memcmp, RpcFilterType };
+use solana_sdk::account::from_account;
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::sysvar;
+
+use lending_pool_client::instructions::{ health_check_accounts, ObligationPositionAccounts };
+use lending_pool_client::math::Decimal;
+use lending_pool_client::pda::{ find_obligation_address, find_pool_address };
+use lending_pool_client::state::{ deserialize_account, oracle_price, pool_at };
+use lending_pool_client::{ LendingPool, Obligation, OracleType, UserPosition, ID };
+
+// UserPosition 中 user、pool 字段的偏移（8 字节 discriminator 之后）
+const POSITION_USER_OFFSET: usize = 8;
+const POSITION_POOL_OFFSET: usize = 8 + 32;
+
+// 账户不存在时返回 None
+pub fn fetch_optional<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<Option<T>> {
+    let account = rpc.get_account_with_commitment(address, rpc.commitment())?.value;
+    account
+        .map(|account| deserialize_account(&account.data).with_context(|| format!("invalid account {address}")))
+        .transpose()
+}
+
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    fetch_optional(rpc, address)?.ok_or_else(|| anyhow!("account {address} not found"))
+}
+
+// 集群当前时间（利息结算与报价有效期均以链上时钟为准）
+pub fn cluster_time(rpc: &RpcClient) -> Result<i64> {
+    let account = rpc.get_account(&sysvar::clock::ID)?;
+    let clock: Clock = from_account(&account).ok_or_else(|| anyhow!("invalid clock sysvar"))?;
+    Ok(clock.unix_timestamp)
+}
+
+// 按 Mint 加载资金池
+pub fn load_pool(rpc: &RpcClient, mint: &Pubkey) -> Result<(Pubkey, LendingPool)> {
+    let address = find_pool_address(mint).0;
+    let pool = fetch(rpc, &address).with_context(|| format!("pool for mint {mint}"))?;
+    Ok((address, pool))
+}
+
+// 按资金池配置读取预言机价格（固定价格模式无需 feed 账户）
+pub fn fetch_price(rpc: &RpcClient, pool: &LendingPool, now: i64) -> Result<Decimal> {
+    let price = if pool.oracle_type == OracleType::Fixed {
+        oracle_price(pool, &Pubkey::default(), &[], now)
+    } else {
+        let feed = rpc.get_account(&pool.oracle_feed).with_context(|| format!("price feed {}", pool.oracle_feed))?;
+        oracle_price(pool, &feed.owner, &feed.data, now)
+    };
+    price.with_context(|| format!("price for mint {}", pool.mint))
+}
+
+// 义务账户中的一个仓位及其资金池（资金池利息已结算到 now）
+pub struct LoadedPosition {
+    pub address: Pubkey,
+    pub position: UserPosition,
+    pub pool_address: Pubkey,
+    pub pool: LendingPool,
+}
+
+// 加载用户义务账户及其中全部仓位；用户尚未建立义务账户时返回 None
+pub fn load_obligation(rpc: &RpcClient, user: &Pubkey, now: i64) -> Result<Option<(Obligation, Vec<LoadedPosition>)>> {
+    let Some(obligation) = fetch_optional::<Obligation>(rpc, &find_obligation_address(user).0)? else {
+        return Ok(None);
+    };
+
+    let mut positions = Vec::new();
+    for address in &obligation.positions[..obligation.position_count as usize] {
+        let position: UserPosition = fetch(rpc, address)?;
+        let pool: LendingPool = fetch(rpc, &position.pool)?;
+        positions.push(LoadedPosition {
+            address: *address,
+            pool_address: position.pool,
+            pool: pool_at(&pool, now)?,
+            position,
+        });
+    }
+    Ok(Some((obligation, positions)))
+}
+
+// 健康检查所需的 remaining_accounts（排除指令本身已传入的仓位）
+pub fn health_accounts(rpc: &RpcClient, user: &Pubkey, exclude: &[Pubkey]) -> Result<Vec<AccountMeta>> {
+    let now = cluster_time(rpc)?;
+    let Some((obligation, positions)) = load_obligation(rpc, user, now)? else {
+        return Ok(vec![]);
+    };
+
+    let positions: Vec<_> = positions
+        .iter()
+        .map(|loaded| ObligationPositionAccounts {
+            pool: loaded.pool_address,
+            position: loaded.address,
+            price_feed: loaded.pool.oracle_feed,
+        })
+        .collect();
+    Ok(health_check_accounts(&obligation, exclude, &positions)?)
+}
+
+// 按用户和/或资金池筛选全部 UserPosition 账户
+pub fn list_positions(rpc: &RpcClient, user: Option<Pubkey>, pool: Option<Pubkey>) -> Result<Vec<(Pubkey, UserPosition)>> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &UserPosition::DISCRIMINATOR))];
+    if let Some(user) = user {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(POSITION_USER_OFFSET, user.as_ref())));
+    }
+    if let Some(pool) = pool {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(POSITION_POOL_OFFSET, pool.as_ref())));
+    }
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let mut positions = rpc
+        .get_program_accounts_with_config(&ID, config)?
+        .into_iter()
+        .map(|(address, account)| Ok((address, deserialize_account(&account.data)?)))
+        .collect::<Result<Vec<_>>>()?;
+    positions.sort_by_key(|(address, position): &(Pubkey, UserPosition)| (position.user, position.pool, *address));
+    Ok(positions)
+}
+
+// 批量加载资金池（同一资金池只请求一次），利息结算到 now
+pub fn load_pools(rpc: &RpcClient, addresses: impl IntoIterator<Item = Pubkey>, now: i64) -> Result<HashMap<Pubkey, LendingPool>> {
+    let mut pools = HashMap::new();
+    for address in addresses {
+        if let Entry::Vacant(entry) = pools.entry(address) {
+            let pool: LendingPool = fetch(rpc, &address)?;
+            entry.insert(pool_at(&pool, now)?);
+        }
+    }
+    Ok(pools)
+}
+
+#[test]
+fn test_position_offsets() {
+    use anchor_lang::AccountSerialize;
+
+    let position = UserPosition { user: Pubkey::new_unique(), pool: Pubkey::new_unique(), ..UserPosition::default() };
+    let mut data = Vec::new();
+    position.try_serialize(&mut data).unwrap();
+
+    assert_eq!(&data[..8], &UserPosition::DISCRIMINATOR);
+    assert_eq!(&data[POSITION_USER_OFFSET..POSITION_USER_OFFSET + 32], position.user.as_ref());
+    assert_eq!(&data[POSITION_POOL_OFFSET..POSITION_POOL_OFFSET + 32], position.pool.as_ref());
+}
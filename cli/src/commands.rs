@@ -0,0 +1,426 @@
+use std::path::PathBuf;
+
+use anyhow::{ anyhow, bail, Result };
+use serde_json::{ json, Value };
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::{ Error as ClientError, ErrorKind as ClientErrorKind };
+use solana_rpc_client_api::request::{ RpcError, RpcResponseErrorData };
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{ read_keypair_file, Keypair, Signature, Signer };
+use solana_sdk::transaction::Transaction;
+
+use lending_pool_client::instructions;
+use lending_pool_client::pda::{
+    find_collateral_vault_address,
+    find_insurance_vault_address,
+    find_pool_address,
+    find_receipt_mint_address,
+    find_user_position_address,
+};
+use lending_pool_client::state::{ obligation_health, pool_at, pool_state, position_balances };
+use lending_pool_client::{
+    InitPoolParams,
+    InterestRateModel,
+    LendingPool,
+    PoolConfigParams,
+    UserPosition,
+    PAUSE_BORROW,
+    PAUSE_DEPOSIT,
+    PAUSE_FLASH_LOAN,
+    PAUSE_LIQUIDATE,
+    PAUSE_REPAY,
+    PAUSE_WITHDRAW,
+};
+
+use crate::accounts::{ cluster_time, fetch_price, health_accounts, load_obligation, load_pool, load_pools };
+use crate::output::{ decimal, health_factor, wad };
+use crate::{ InitPoolArgs, UpdateConfigArgs };
+
+// 可暂停操作的名称（paused_operations 位标志）
+const PAUSE_FLAGS: [(u8, &str); 6] = [
+    (PAUSE_DEPOSIT, "deposit"),
+    (PAUSE_WITHDRAW, "withdraw"),
+    (PAUSE_BORROW, "borrow"),
+    (PAUSE_REPAY, "repay"),
+    (PAUSE_LIQUIDATE, "liquidate"),
+    (PAUSE_FLASH_LOAN, "flash_loan"),
+];
+
+pub struct Config {
+    pub rpc: RpcClient,
+    keypair: Option<PathBuf>,
+}
+
+impl Config {
+    pub fn new(url: String, keypair: Option<PathBuf>) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(url, CommitmentConfig::confirmed()),
+            keypair,
+        }
+    }
+
+    // 签名钱包，未指定时与 solana CLI 相同使用 ~/.config/solana/id.json
+    fn payer(&self) -> Result<Keypair> {
+        let path = match &self.keypair {
+            Some(path) => path.clone(),
+            None => {
+                let home = std::env::var_os("HOME").ok_or_else(|| anyhow!("HOME is not set, pass --keypair"))?;
+                PathBuf::from(home).join(".config/solana/id.json")
+            }
+        };
+        read_keypair_file(&path).map_err(|err| anyhow!("failed to read keypair {}: {err}", path.display()))
+    }
+
+    // 未指定用户时使用签名钱包地址
+    fn user_or_payer(&self, user: Option<Pubkey>) -> Result<Pubkey> {
+        match user {
+            Some(user) => Ok(user),
+            None => Ok(self.payer()?.pubkey()),
+        }
+    }
+
+    // 签名、发送并等待确认
+    fn send(&self, payer: &Keypair, instructions: &[Instruction]) -> Result<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &[payer], blockhash);
+        self.rpc.send_and_confirm_transaction(&transaction).map_err(send_error)
+    }
+}
+
+// 预检失败时附带程序日志，便于定位链上错误
+fn send_error(err: ClientError) -> anyhow::Error {
+    if
+        let ClientErrorKind::RpcError(
+            RpcError::RpcResponseError { data: RpcResponseErrorData::SendTransactionPreflightFailure(result), .. },
+        ) = err.kind()
+    {
+        if let Some(logs) = result.logs.as_ref().filter(|logs| !logs.is_empty()) {
+            return anyhow!("{err}\n{}", logs.join("\n"));
+        }
+    }
+    err.into()
+}
+
+// ----------------------------
+// 管理命令
+// ----------------------------
+
+pub fn init_pool(config: &Config, args: InitPoolArgs) -> Result<Value> {
+    let payer = config.payer()?;
+    let params = InitPoolParams {
+        reserve_factor: args.reserve_factor,
+        loan_to_value: args.loan_to_value,
+        liquidation_threshold: args.liquidation_threshold,
+        rate_model: InterestRateModel {
+            optimal_utilization_bps: args.optimal_utilization_bps,
+            base_rate_bps: args.base_rate_bps,
+            slope1_bps: args.slope1_bps,
+            slope2_bps: args.slope2_bps,
+            max_rate_bps: args.max_rate_bps,
+            ..InterestRateModel::default()
+        },
+        liquidation_bonus: args.liquidation_bonus,
+        close_factor: args.close_factor,
+        max_staleness: args.max_staleness,
+        oracle_type: args.oracle_type.into(),
+        oracle_fixed_price: args.oracle_fixed_price,
+        treasury: args.treasury,
+        fee_authority: args.fee_authority.unwrap_or(payer.pubkey()),
+        flash_loan_fee_bps: args.flash_loan_fee_bps,
+        supply_cap: args.supply_cap,
+        borrow_cap: args.borrow_cap,
+        pause_guardian: args.pause_guardian.unwrap_or(payer.pubkey()),
+        insurance_fund_factor: args.insurance_fund_factor,
+        receipt_name: args.receipt_name,
+        receipt_symbol: args.receipt_symbol,
+        receipt_uri: args.receipt_uri,
+    };
+
+    let instruction = instructions::initialize_pool(
+        &payer.pubkey(),
+        &args.mint,
+        &args.price_feed,
+        &args.feed_program,
+        params
+    );
+    let signature = config.send(&payer, &[instruction])?;
+
+    let pool = find_pool_address(&args.mint).0;
+    Ok(json!({
+        "signature": signature.to_string(),
+        "pool": pool.to_string(),
+        "receipt_mint": find_receipt_mint_address(&pool).0.to_string(),
+        "insurance_vault": find_insurance_vault_address(&pool).0.to_string(),
+        "collateral_vault": find_collateral_vault_address(&pool).0.to_string(),
+    }))
+}
+
+pub fn update_config(config: &Config, args: UpdateConfigArgs) -> Result<Value> {
+    let payer = config.payer()?;
+    let (address, pool) = load_pool(&config.rpc, &args.mint)?;
+    let params = PoolConfigParams {
+        reserve_factor: args.reserve_factor,
+        loan_to_value: args.loan_to_value,
+        liquidation_threshold: args.liquidation_threshold,
+        liquidation_bonus: args.liquidation_bonus,
+        close_factor: args.close_factor,
+        oracle_program: args.oracle_program,
+        oracle_feed: args.oracle_feed,
+        max_staleness: args.max_staleness,
+        oracle_type: args.oracle_type.map(Into::into),
+        oracle_fixed_price: args.oracle_fixed_price,
+        treasury: args.treasury,
+        fee_authority: args.fee_authority,
+        flash_loan_fee_bps: args.flash_loan_fee_bps,
+        supply_cap: args.supply_cap,
+        borrow_cap: args.borrow_cap,
+        pause_guardian: args.pause_guardian,
+        insurance_fund_factor: args.insurance_fund_factor,
+    };
+
+    let instruction = instructions::update_pool_config(&payer.pubkey(), &pool, params);
+    let signature = config.send(&payer, &[instruction])?;
+    Ok(json!({ "signature": signature.to_string(), "pool": address.to_string() }))
+}
+
+// ----------------------------
+// 用户命令
+// ----------------------------
+
+pub fn deposit(config: &Config, mint: &Pubkey, amount: u64, receipt: bool) -> Result<Value> {
+    let payer = config.payer()?;
+    let (address, pool) = load_pool(&config.rpc, mint)?;
+
+    let instruction = if receipt {
+        instructions::deposit(&payer.pubkey(), &pool, amount)
+    } else {
+        instructions::deposit_collateral(&payer.pubkey(), &pool, amount)
+    };
+    let signature = config.send(&payer, &[instruction])?;
+    Ok(json!({ "signature": signature.to_string(), "pool": address.to_string(), "amount": amount }))
+}
+
+pub fn withdraw(config: &Config, mint: &Pubkey, amount: u64, receipt: bool) -> Result<Value> {
+    let payer = config.payer()?;
+    let user = payer.pubkey();
+    let (address, pool) = load_pool(&config.rpc, mint)?;
+
+    let instruction = if receipt {
+        instructions::redeem(&user, &pool, amount)
+    } else {
+        let position = find_user_position_address(&user, &address).0;
+        let remaining_accounts = health_accounts(&config.rpc, &user, &[position])?;
+        instructions::withdraw(&user, &pool, amount, remaining_accounts)
+    };
+    let signature = config.send(&payer, &[instruction])?;
+    Ok(json!({ "signature": signature.to_string(), "pool": address.to_string(), "amount": amount }))
+}
+
+pub fn borrow(config: &Config, mint: &Pubkey, amount: u64) -> Result<Value> {
+    let payer = config.payer()?;
+    let user = payer.pubkey();
+    let (address, pool) = load_pool(&config.rpc, mint)?;
+
+    let position = find_user_position_address(&user, &address).0;
+    let remaining_accounts = health_accounts(&config.rpc, &user, &[position])?;
+    let instruction = instructions::borrow(&user, &pool, amount, remaining_accounts);
+    let signature = config.send(&payer, &[instruction])?;
+    Ok(json!({ "signature": signature.to_string(), "pool": address.to_string(), "amount": amount }))
+}
+
+pub fn repay(config: &Config, mint: &Pubkey, amount: u64) -> Result<Value> {
+    let payer = config.payer()?;
+    let (address, pool) = load_pool(&config.rpc, mint)?;
+
+    let instruction = instructions::repay(&payer.pubkey(), &pool, amount);
+    let signature = config.send(&payer, &[instruction])?;
+    Ok(json!({ "signature": signature.to_string(), "pool": address.to_string(), "amount": amount }))
+}
+
+pub fn liquidate(
+    config: &Config,
+    user: &Pubkey,
+    repay_mint: &Pubkey,
+    seize_mint: &Pubkey,
+    amount: u64
+) -> Result<Value> {
+    let payer = config.payer()?;
+    let (repay_address, repay_pool) = load_pool(&config.rpc, repay_mint)?;
+    let (seize_address, seize_pool) = load_pool(&config.rpc, seize_mint)?;
+
+    let exclude = [
+        find_user_position_address(user, &repay_address).0,
+        find_user_position_address(user, &seize_address).0,
+    ];
+    let remaining_accounts = health_accounts(&config.rpc, user, &exclude)?;
+    // 清算人的还款与收款代币账户须已存在（首次清算时先创建）
+    let liquidator = payer.pubkey();
+    let mut ixs = vec![instructions::create_user_token_account(&liquidator, &liquidator, repay_mint)];
+    // 债务与抵押品在同一资金池时使用同池清算指令
+    if repay_address == seize_address {
+        ixs.push(instructions::liquidate_same_pool(&liquidator, user, &repay_pool, amount, remaining_accounts));
+    } else {
+        ixs.push(instructions::create_user_token_account(&liquidator, &liquidator, seize_mint));
+        ixs.push(instructions::liquidate(&liquidator, user, &repay_pool, &seize_pool, amount, remaining_accounts));
+    }
+    let signature = config.send(&payer, &ixs)?;
+    Ok(json!({
+        "signature": signature.to_string(),
+        "user": user.to_string(),
+        "repay_pool": repay_address.to_string(),
+        "seize_pool": seize_address.to_string(),
+        "repay_amount": amount,
+    }))
+}
+
+// ----------------------------
+// 查询命令
+// ----------------------------
+
+pub fn show_pool(config: &Config, mint: &Pubkey) -> Result<Value> {
+    let now = cluster_time(&config.rpc)?;
+    let (address, stored) = load_pool(&config.rpc, mint)?;
+    let pool = pool_at(&stored, now)?;
+    let state = pool_state(&pool)?;
+    // 报价不可用（如已过期）时不影响其余信息的展示
+    let price = fetch_price(&config.rpc, &pool, now).map(decimal).unwrap_or(Value::Null);
+
+    let rate_model = &pool.rate_model;
+    let extra_kinks: Vec<_> = rate_model.extra_kinks[..rate_model.extra_kink_count as usize]
+        .iter()
+        .map(|kink| json!({ "utilization_bps": kink.utilization_bps, "rate_bps": kink.rate_bps }))
+        .collect();
+    let paused: Vec<_> = PAUSE_FLAGS.iter()
+        .filter(|(flag, _)| pool.paused_operations & flag != 0)
+        .map(|(_, name)| *name)
+        .collect();
+
+    Ok(json!({
+        "address": address.to_string(),
+        "mint": pool.mint.to_string(),
+        "decimals": pool.decimals,
+        "authority": pool.authority.to_string(),
+        "pending_authority": optional_key(&pool.pending_authority),
+        "fee_authority": pool.fee_authority.to_string(),
+        "pause_guardian": pool.pause_guardian.to_string(),
+        "paused_operations": paused,
+        "receipt_mint": pool.receipt_mint.to_string(),
+        "treasury": pool.treasury.to_string(),
+        "insurance_vault": pool.insurance_vault.to_string(),
+        "collateral_vault": pool.collateral_vault.to_string(),
+        "oracle": {
+            "type": format!("{:?}", pool.oracle_type),
+            "program": pool.oracle_program.to_string(),
+            "feed": pool.oracle_feed.to_string(),
+            "max_staleness": pool.max_staleness,
+            "price": price,
+        },
+        "config": {
+            "reserve_factor": pool.reserve_factor,
+            "loan_to_value": pool.loan_to_value,
+            "liquidation_threshold": pool.liquidation_threshold,
+            "liquidation_bonus": pool.liquidation_bonus,
+            "close_factor": pool.close_factor,
+            "flash_loan_fee_bps": pool.flash_loan_fee_bps,
+            "insurance_fund_factor": pool.insurance_fund_factor,
+            "supply_cap": pool.supply_cap,
+            "borrow_cap": pool.borrow_cap,
+            "rate_model": {
+                "optimal_utilization_bps": rate_model.optimal_utilization_bps,
+                "base_rate_bps": rate_model.base_rate_bps,
+                "slope1_bps": rate_model.slope1_bps,
+                "slope2_bps": rate_model.slope2_bps,
+                "max_rate_bps": rate_model.max_rate_bps,
+                "extra_kinks": extra_kinks,
+            },
+        },
+        "state": {
+            "total_supply": state.total_supply,
+            "total_borrowed": state.total_borrowed,
+            "liquidity_index": wad(state.liquidity_index),
+            "borrow_index": wad(state.borrow_index),
+            "utilization_rate": wad(state.utilization_rate),
+            "borrow_rate": wad(state.borrow_rate),
+            "supply_rate": wad(state.supply_rate),
+            "accumulated_protocol_fees": state.accumulated_protocol_fees,
+            "last_update_time": stored.last_update_time,
+            "accrued_to": now,
+        },
+    }))
+}
+
+pub fn show_position(config: &Config, user: Option<Pubkey>, mint: Option<Pubkey>) -> Result<Value> {
+    let user = config.user_or_payer(user)?;
+    let now = cluster_time(&config.rpc)?;
+    let Some((_, positions)) = load_obligation(&config.rpc, &user, now)? else {
+        bail!("user {user} has no obligation");
+    };
+
+    // 健康因子按义务账户中的全部仓位计算
+    let prices = positions
+        .iter()
+        .map(|loaded| fetch_price(&config.rpc, &loaded.pool, now))
+        .collect::<Result<Vec<_>>>()?;
+    let valued: Vec<_> = positions
+        .iter()
+        .zip(&prices)
+        .map(|(loaded, price)| (&loaded.pool, &loaded.position, *price))
+        .collect();
+    let health = obligation_health(&valued)?;
+
+    let mut entries = Vec::new();
+    for (loaded, price) in positions.iter().zip(&prices) {
+        if mint.is_some_and(|mint| mint != loaded.pool.mint) {
+            continue;
+        }
+        let mut entry = position_json(&loaded.address, &loaded.position, &loaded.pool)?;
+        entry["price"] = decimal(*price);
+        entries.push(entry);
+    }
+
+    Ok(json!({
+        "user": user.to_string(),
+        "borrow_health": health_factor(health.borrow_health),
+        "liquidation_health": health_factor(health.liquidation_health),
+        "status": format!("{:?}", health.status),
+        "positions": entries,
+    }))
+}
+
+pub fn list_positions(config: &Config, user: Option<Pubkey>, mint: Option<Pubkey>) -> Result<Value> {
+    let pool = mint.map(|mint| find_pool_address(&mint).0);
+    let positions = crate::accounts::list_positions(&config.rpc, user, pool)?;
+
+    let now = cluster_time(&config.rpc)?;
+    let pools = load_pools(&config.rpc, positions.iter().map(|(_, position)| position.pool), now)?;
+
+    let entries = positions
+        .iter()
+        .map(|(address, position)| position_json(address, position, &pools[&position.pool]))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(json!({ "positions": entries }))
+}
+
+// 仓位余额（资金池应已结算到当前时间）
+fn position_json(address: &Pubkey, position: &UserPosition, pool: &LendingPool) -> Result<Value> {
+    let balances = position_balances(position, pool)?;
+    Ok(json!({
+        "address": address.to_string(),
+        "user": position.user.to_string(),
+        "pool": position.pool.to_string(),
+        "mint": pool.mint.to_string(),
+        "collateral_enabled": position.collateral_enabled,
+        "deposited": balances.deposited,
+        "borrowed": balances.borrowed,
+        "collateral": balances.collateral,
+        "scaled_deposited_amount": position.scaled_deposited_amount,
+        "scaled_borrowed_amount": position.scaled_borrowed_amount,
+    }))
+}
+
+// 未设置的地址（全零）输出为空
+fn optional_key(key: &Pubkey) -> Value {
+    if *key == Pubkey::default() { Value::Null } else { Value::String(key.to_string()) }
+}
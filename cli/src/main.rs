@@ -0,0 +1,288 @@
+// lending_pool 命令行工具：通过任意 RPC 节点（包括本地 solana-test-validator）管理资金池与仓位
+// - 交易类命令使用 --keypair 指定的钱包签名并支付手续费
+// - 数量均为代币最小单位（与链上指令参数一致）
+// - --output json 输出 JSON，便于脚本处理
+mod accounts;
+mod commands;
+mod output;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{ Args, Parser, Subcommand, ValueEnum };
+use solana_sdk::pubkey::Pubkey;
+
+use lending_pool_client::OracleType;
+
+use crate::commands::Config;
+use crate::output::OutputFormat;
+
+#[derive(Parser, Debug)]
+#[command(name = "lending-pool-cli", version, about = "通过 RPC 管理 lending_pool 资金池与仓位")]
+struct Cli {
+    /// RPC 节点地址
+    #[arg(long, short = 'u', global = true, env = "LENDING_POOL_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// 签名钱包（默认 ~/.config/solana/id.json）
+    #[arg(long, short = 'k', global = true, env = "LENDING_POOL_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    /// 输出格式
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 创建资金池（签名钱包成为管理员）
+    InitPool(InitPoolArgs),
+    /// 更新资金池参数（未指定的参数保持不变）
+    UpdateConfig(UpdateConfigArgs),
+    /// 存款：默认存入仓位并启用抵押，--receipt 时铸造存款凭证
+    Deposit {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        /// 存入资金池并铸造可转让的存款凭证（cToken）
+        #[arg(long)]
+        receipt: bool,
+    },
+    /// 取款：默认从仓位取出，--receipt 时赎回存款凭证（amount 为凭证数量）
+    Withdraw {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        amount: u64,
+        /// 赎回存款凭证（cToken）
+        #[arg(long)]
+        receipt: bool,
+    },
+    /// 借款
+    Borrow {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// 还款（超出债务的部分按债务计算）
+    Repay {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// 清算：偿还 user 在 repay-mint 资金池的债务，获得其在 seize-mint 资金池的抵押品（两者可相同）
+    Liquidate {
+        #[arg(long)]
+        user: Pubkey,
+        #[arg(long)]
+        repay_mint: Pubkey,
+        #[arg(long)]
+        seize_mint: Pubkey,
+        #[arg(long)]
+        amount: u64,
+    },
+    /// 查看资金池参数与当前状态（利息结算到集群当前时间）
+    ShowPool {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// 查看用户义务账户中的仓位与健康因子（默认签名钱包）
+    ShowPosition {
+        #[arg(long)]
+        user: Option<Pubkey>,
+        /// 只显示该资金池的仓位（健康因子仍按全部仓位计算）
+        #[arg(long)]
+        mint: Option<Pubkey>,
+    },
+    /// 列出用户仓位（可按用户或资金池过滤）
+    ListPositions {
+        #[arg(long)]
+        user: Option<Pubkey>,
+        #[arg(long)]
+        mint: Option<Pubkey>,
+    },
+}
+
+// 预言机类型（命令行取值）
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum OracleKind {
+    Chainlink,
+    Pyth,
+    Switchboard,
+    Fixed,
+}
+
+impl From<OracleKind> for OracleType {
+    fn from(kind: OracleKind) -> Self {
+        match kind {
+            OracleKind::Chainlink => OracleType::Chainlink,
+            OracleKind::Pyth => OracleType::Pyth,
+            OracleKind::Switchboard => OracleType::Switchboard,
+            OracleKind::Fixed => OracleType::Fixed,
+        }
+    }
+}
+
+// 默认值与 tests/lending-pool.ts 保持一致
+#[derive(Args, Debug)]
+struct InitPoolArgs {
+    #[arg(long)]
+    mint: Pubkey,
+    /// 价格 feed 账户
+    #[arg(long)]
+    price_feed: Pubkey,
+    /// 预言机程序（feed 账户的所有者）
+    #[arg(long)]
+    feed_program: Pubkey,
+    #[arg(long, value_enum, default_value_t = OracleKind::Chainlink)]
+    oracle_type: OracleKind,
+    /// 固定价格（仅 fixed，12 位小数）
+    #[arg(long, default_value_t = 0)]
+    oracle_fixed_price: u128,
+    /// 报价最长有效期（秒）
+    #[arg(long, default_value_t = 60)]
+    max_staleness: i64,
+    /// 协议手续费接收账户（同一 Mint 的代币账户）
+    #[arg(long)]
+    treasury: Pubkey,
+    /// 手续费管理员（默认签名钱包）
+    #[arg(long)]
+    fee_authority: Option<Pubkey>,
+    /// 暂停守护者（默认签名钱包）
+    #[arg(long)]
+    pause_guardian: Option<Pubkey>,
+    #[arg(long, default_value_t = 10)]
+    reserve_factor: u8,
+    #[arg(long, default_value_t = 75)]
+    loan_to_value: u8,
+    #[arg(long, default_value_t = 80)]
+    liquidation_threshold: u8,
+    #[arg(long, default_value_t = 5)]
+    liquidation_bonus: u8,
+    #[arg(long, default_value_t = 50)]
+    close_factor: u8,
+    #[arg(long, default_value_t = 9)]
+    flash_loan_fee_bps: u16,
+    /// 总供应上限（0 表示不限）
+    #[arg(long, default_value_t = 0)]
+    supply_cap: u64,
+    /// 总借款上限（0 表示不限）
+    #[arg(long, default_value_t = 0)]
+    borrow_cap: u64,
+    #[arg(long, default_value_t = 20)]
+    insurance_fund_factor: u8,
+    #[arg(long, default_value_t = 8000)]
+    optimal_utilization_bps: u16,
+    #[arg(long, default_value_t = 200)]
+    base_rate_bps: u32,
+    #[arg(long, default_value_t = 800)]
+    slope1_bps: u32,
+    #[arg(long, default_value_t = 10000)]
+    slope2_bps: u32,
+    #[arg(long, default_value_t = 10200)]
+    max_rate_bps: u32,
+    /// 存款凭证名称（Metaplex 元数据）
+    #[arg(long)]
+    receipt_name: String,
+    #[arg(long)]
+    receipt_symbol: String,
+    #[arg(long, default_value = "")]
+    receipt_uri: String,
+}
+
+#[derive(Args, Debug)]
+struct UpdateConfigArgs {
+    #[arg(long)]
+    mint: Pubkey,
+    #[arg(long)]
+    reserve_factor: Option<u8>,
+    #[arg(long)]
+    loan_to_value: Option<u8>,
+    #[arg(long)]
+    liquidation_threshold: Option<u8>,
+    #[arg(long)]
+    liquidation_bonus: Option<u8>,
+    #[arg(long)]
+    close_factor: Option<u8>,
+    #[arg(long)]
+    oracle_program: Option<Pubkey>,
+    #[arg(long)]
+    oracle_feed: Option<Pubkey>,
+    #[arg(long)]
+    max_staleness: Option<i64>,
+    #[arg(long, value_enum)]
+    oracle_type: Option<OracleKind>,
+    #[arg(long)]
+    oracle_fixed_price: Option<u128>,
+    #[arg(long)]
+    treasury: Option<Pubkey>,
+    #[arg(long)]
+    fee_authority: Option<Pubkey>,
+    #[arg(long)]
+    flash_loan_fee_bps: Option<u16>,
+    #[arg(long)]
+    supply_cap: Option<u64>,
+    #[arg(long)]
+    borrow_cap: Option<u64>,
+    #[arg(long)]
+    pause_guardian: Option<Pubkey>,
+    #[arg(long)]
+    insurance_fund_factor: Option<u8>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let format = cli.output;
+    let config = Config::new(cli.url, cli.keypair);
+
+    let result = match cli.command {
+        Command::InitPool(args) => commands::init_pool(&config, args),
+        Command::UpdateConfig(args) => commands::update_config(&config, args),
+        Command::Deposit { mint, amount, receipt } => commands::deposit(&config, &mint, amount, receipt),
+        Command::Withdraw { mint, amount, receipt } => commands::withdraw(&config, &mint, amount, receipt),
+        Command::Borrow { mint, amount } => commands::borrow(&config, &mint, amount),
+        Command::Repay { mint, amount } => commands::repay(&config, &mint, amount),
+        Command::Liquidate { user, repay_mint, seize_mint, amount } =>
+            commands::liquidate(&config, &user, &repay_mint, &seize_mint, amount),
+        Command::ShowPool { mint } => commands::show_pool(&config, &mint),
+        Command::ShowPosition { user, mint } => commands::show_position(&config, user, mint),
+        Command::ListPositions { user, mint } => commands::list_positions(&config, user, mint),
+    };
+
+    match result {
+        Ok(value) => {
+            output::print(format, &value);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            output::print_error(format, &err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[test]
+fn test_cli_definition() {
+    use clap::CommandFactory;
+
+    Cli::command().debug_assert();
+
+    let cli = Cli::try_parse_from([
+        "lending-pool-cli",
+        "--output",
+        "json",
+        "deposit",
+        "--mint",
+        "So11111111111111111111111111111111111111112",
+        "--amount",
+        "1000",
+    ]).unwrap();
+    assert!(matches!(cli.output, OutputFormat::Json));
+    assert!(matches!(cli.command, Command::Deposit { amount: 1000, receipt: false, .. }));
+}
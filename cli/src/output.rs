@@ -0,0 +1,105 @@
+use clap::ValueEnum;
+use serde_json::{ json, Value };
+
+use lending_pool_client::health::HealthFactor;
+use lending_pool_client::math::Decimal;
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum OutputFormat {
+    Text, // 每行一个 "字段: 值"
+    Json, // 单个 JSON 对象
+}
+
+pub fn print(format: OutputFormat, value: &Value) {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value).unwrap_or_default()),
+        OutputFormat::Text => {
+            let mut lines = Vec::new();
+            flatten("", value, &mut lines);
+            for (key, value) in lines {
+                println!("{key}: {value}");
+            }
+        }
+    }
+}
+
+// JSON 模式下错误同样以 JSON 输出到 stderr，便于脚本解析
+pub fn print_error(format: OutputFormat, err: &anyhow::Error) {
+    match format {
+        OutputFormat::Json => eprintln!("{}", json!({ "error": error_message(err) })),
+        OutputFormat::Text => eprintln!("error: {}", error_message(err)),
+    }
+}
+
+// 逐层拼接错误原因，跳过已包含在上层描述中的原因（RPC 错误会重复其来源）
+fn error_message(err: &anyhow::Error) -> String {
+    let mut message = String::new();
+    for cause in err.chain() {
+        let cause = cause.to_string();
+        if message.contains(&cause) {
+            continue;
+        }
+        if !message.is_empty() {
+            message.push_str(": ");
+        }
+        message.push_str(&cause);
+    }
+    message
+}
+
+// 将嵌套对象展开为 a.b.c 形式的键，数组元素以下标区分
+fn flatten(prefix: &str, value: &Value, lines: &mut Vec<(String, String)>) {
+    let key = |name: &str| if prefix.is_empty() { name.to_string() } else { format!("{prefix}.{name}") };
+    match value {
+        Value::Object(map) => {
+            for (name, value) in map {
+                flatten(&key(name), value, lines);
+            }
+        }
+        Value::Array(items) => {
+            if items.is_empty() {
+                lines.push((prefix.to_string(), "[]".to_string()));
+            }
+            for (i, value) in items.iter().enumerate() {
+                flatten(&key(&i.to_string()), value, lines);
+            }
+        }
+        Value::String(s) => lines.push((prefix.to_string(), s.clone())),
+        Value::Null => lines.push((prefix.to_string(), "-".to_string())),
+        other => lines.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+// 18 位小数的指数、利率等以十进制字符串输出（u128 超出 JSON 数值的安全范围）
+pub fn wad(scaled_val: u128) -> Value {
+    Value::String(Decimal::from_scaled_val(scaled_val).to_string())
+}
+
+pub fn decimal(value: Decimal) -> Value {
+    Value::String(value.to_string())
+}
+
+// 无债务时健康因子为 "inf"
+pub fn health_factor(health: HealthFactor) -> Value {
+    if health.is_infinite() { Value::String("inf".to_string()) } else { wad(health.0) }
+}
+
+#[test]
+fn test_flatten() {
+    let value = json!({
+        "pool": { "total_supply": 100, "borrow_rate": "0.05" },
+        "positions": [{ "deposited": 1 }],
+        "price": null,
+    });
+    let mut lines = Vec::new();
+    flatten("", &value, &mut lines);
+
+    let lines: Vec<_> = lines.iter().map(|(k, v)| format!("{k}: {v}")).collect();
+    assert!(lines.contains(&"pool.total_supply: 100".to_string()));
+    assert!(lines.contains(&"pool.borrow_rate: 0.05".to_string()));
+    assert!(lines.contains(&"positions.0.deposited: 1".to_string()));
+    assert!(lines.contains(&"price: -".to_string()));
+
+    assert_eq!(health_factor(HealthFactor::INFINITE), json!("inf"));
+    assert_eq!(wad(1_500_000_000_000_000_000), json!("1.500000000000000000"));
+}
@@ -0,0 +1,539 @@
+use anchor_lang::prelude::{ AccountMeta, Pubkey, Result };
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{ system_program, sysvar };
+use anchor_lang::{ require, InstructionData, ToAccountMetas };
+use anchor_spl::{ associated_token, metadata::mpl_token_metadata, token };
+
+use lending_pool::{ accounts, instruction, ID };
+use lending_pool::model::{
+    InitPoolParams,
+    InterestRateModel,
+    LendingError,
+    LendingPool,
+    Obligation,
+    PoolConfigParams,
+};
+
+#[cfg(feature = "event-cpi")]
+use crate::pda::find_event_authority_address;
+use crate::pda::{
+    find_collateral_vault_address,
+    find_insurance_vault_address,
+    find_obligation_address,
+    find_pool_address,
+    find_receipt_metadata_address,
+    find_receipt_mint_address,
+    find_user_position_address,
+    pool_token_account,
+    user_receipt_account,
+    user_token_account,
+};
+
+// 构造指令账户；启用 event-cpi 特性时附加事件权限 PDA 与本程序
+macro_rules! event_accounts {
+    ($name:ident { $($field:ident: $value:expr),* $(,)? }) => {
+        accounts::$name {
+            $($field: $value,)*
+            #[cfg(feature = "event-cpi")]
+            event_authority: find_event_authority_address().0,
+            #[cfg(feature = "event-cpi")]
+            program: ID,
+        }
+    };
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData, remaining_accounts: Vec<AccountMeta>) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining_accounts);
+    Instruction::new_with_bytes(ID, &data.data(), metas)
+}
+
+// ----------------------------
+// 义务账户的 remaining_accounts
+// ----------------------------
+
+// 义务账户中一个仓位对应的账户
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ObligationPositionAccounts {
+    pub pool: Pubkey, // 仓位所属资金池
+    pub position: Pubkey, // 用户仓位
+    pub price_feed: Pubkey, // 资金池配置的价格 feed
+}
+
+impl ObligationPositionAccounts {
+    pub fn new(user: &Pubkey, pool: &LendingPool) -> Self {
+        let pool_key = find_pool_address(&pool.mint).0;
+        Self {
+            pool: pool_key,
+            position: find_user_position_address(user, &pool_key).0,
+            price_feed: pool.oracle_feed,
+        }
+    }
+}
+
+// 按义务账户中的顺序排列除 exclude 外的仓位，positions 需覆盖这些仓位（顺序不限）
+fn ordered_positions(
+    obligation: &Obligation,
+    exclude: &[Pubkey],
+    positions: &[ObligationPositionAccounts]
+) -> Result<Vec<ObligationPositionAccounts>> {
+    let mut ordered = Vec::new();
+    for key in &obligation.positions[..obligation.position_count as usize] {
+        if exclude.contains(key) {
+            continue;
+        }
+        let position = positions.iter().find(|accounts| accounts.position == *key);
+        require!(position.is_some(), LendingError::InvalidObligationAccounts);
+        ordered.extend(position);
+    }
+    Ok(ordered)
+}
+
+// 健康检查所需的 remaining_accounts：[资金池, 用户仓位, 价格 feed] 三个一组
+// 用于 borrow / withdraw / withdraw_collateral / set_collateral_enabled / liquidate / liquidate_same_pool
+pub fn health_check_accounts(
+    obligation: &Obligation,
+    exclude: &[Pubkey],
+    positions: &[ObligationPositionAccounts]
+) -> Result<Vec<AccountMeta>> {
+    Ok(
+        ordered_positions(obligation, exclude, positions)?
+            .into_iter()
+            .flat_map(|accounts| [
+                AccountMeta::new_readonly(accounts.pool, false),
+                AccountMeta::new_readonly(accounts.position, false),
+                AccountMeta::new_readonly(accounts.price_feed, false),
+            ])
+            .collect()
+    )
+}
+
+// settle_bad_debt 所需的 remaining_accounts：其余用户仓位
+pub fn bad_debt_accounts(
+    obligation: &Obligation,
+    exclude: &[Pubkey],
+    positions: &[ObligationPositionAccounts]
+) -> Result<Vec<AccountMeta>> {
+    Ok(
+        ordered_positions(obligation, exclude, positions)?
+            .into_iter()
+            .map(|accounts| AccountMeta::new_readonly(accounts.position, false))
+            .collect()
+    )
+}
+
+// ----------------------------
+// 管理指令
+// ----------------------------
+
+pub fn initialize_pool(
+    authority: &Pubkey,
+    mint: &Pubkey,
+    price_feed: &Pubkey,
+    feed_program: &Pubkey,
+    params: InitPoolParams
+) -> Instruction {
+    let pool_key = find_pool_address(mint).0;
+    let receipt_mint_key = find_receipt_mint_address(&pool_key).0;
+    let accounts = event_accounts!(InitializePool {
+        pool: pool_key,
+        mint: *mint,
+        receipt_mint: receipt_mint_key,
+        receipt_metadata: find_receipt_metadata_address(&receipt_mint_key).0,
+        insurance_vault: find_insurance_vault_address(&pool_key).0,
+        collateral_vault: find_collateral_vault_address(&pool_key).0,
+        authority: *authority,
+        price_feed: *price_feed,
+        feed_program: *feed_program,
+        token_program: token::ID,
+        token_metadata_program: mpl_token_metadata::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    });
+    build(accounts, instruction::InitializePool { params }, vec![])
+}
+
+pub fn update_pool_config(authority: &Pubkey, pool: &LendingPool, params: PoolConfigParams) -> Instruction {
+    let accounts = event_accounts!(UpdatePoolConfig {
+        pool: find_pool_address(&pool.mint).0,
+        authority: *authority,
+    });
+    build(accounts, instruction::UpdatePoolConfig { params }, vec![])
+}
+
+pub fn update_rate_model(authority: &Pubkey, pool: &LendingPool, rate_model: InterestRateModel) -> Instruction {
+    let accounts = event_accounts!(UpdatePoolConfig {
+        pool: find_pool_address(&pool.mint).0,
+        authority: *authority,
+    });
+    build(accounts, instruction::UpdateRateModel { rate_model }, vec![])
+}
+
+pub fn collect_protocol_fees(authority: &Pubkey, pool: &LendingPool) -> Instruction {
+    let accounts = event_accounts!(CollectProtocolFees {
+        pool: find_pool_address(&pool.mint).0,
+        pool_token_account: pool_token_account(&pool.mint),
+        treasury: pool.treasury,
+        insurance_vault: pool.insurance_vault,
+        authority: *authority,
+        token_program: token::ID,
+    });
+    build(accounts, instruction::CollectProtocolFees {}, vec![])
+}
+
+// remaining_accounts 由 bad_debt_accounts 生成
+pub fn settle_bad_debt(
+    authority: &Pubkey,
+    user: &Pubkey,
+    pool: &LendingPool,
+    remaining_accounts: Vec<AccountMeta>
+) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(SettleBadDebt {
+        user: *user,
+        user_position: find_user_position_address(user, &pool_key).0,
+        obligation: find_obligation_address(user).0,
+        pool: pool_key,
+        pool_token_account: pool_token_account(&pool.mint),
+        insurance_vault: pool.insurance_vault,
+        authority: *authority,
+        token_program: token::ID,
+    });
+    build(accounts, instruction::SettleBadDebt {}, remaining_accounts)
+}
+
+pub fn set_paused_operations(authority: &Pubkey, pool: &LendingPool, paused_operations: u8) -> Instruction {
+    let accounts = event_accounts!(SetPausedOperations {
+        pool: find_pool_address(&pool.mint).0,
+        authority: *authority,
+    });
+    build(accounts, instruction::SetPausedOperations { paused_operations }, vec![])
+}
+
+pub fn propose_authority(authority: &Pubkey, pool: &LendingPool, new_authority: Pubkey) -> Instruction {
+    let accounts = event_accounts!(ProposeAuthority {
+        pool: find_pool_address(&pool.mint).0,
+        authority: *authority,
+    });
+    build(accounts, instruction::ProposeAuthority { new_authority }, vec![])
+}
+
+pub fn accept_authority(pending_authority: &Pubkey, pool: &LendingPool) -> Instruction {
+    let accounts = event_accounts!(AcceptAuthority {
+        pool: find_pool_address(&pool.mint).0,
+        pending_authority: *pending_authority,
+    });
+    build(accounts, instruction::AcceptAuthority {}, vec![])
+}
+
+// ----------------------------
+// 用户指令（代币账户均使用用户的 ATA）
+// ----------------------------
+
+// 创建用户的代币账户（ATA），已存在时不报错
+pub fn create_user_token_account(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+        payer,
+        owner,
+        mint,
+        &token::ID
+    )
+}
+
+pub fn deposit(user: &Pubkey, pool: &LendingPool, amount: u64) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(Deposit {
+        user: *user,
+        user_token_account: user_token_account(user, &pool.mint),
+        user_receipt_account: user_receipt_account(user, &pool.mint),
+        pool: pool_key,
+        pool_token_account: pool_token_account(&pool.mint),
+        receipt_mint: pool.receipt_mint,
+        token_program: token::ID,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    });
+    build(accounts, instruction::Deposit { amount }, vec![])
+}
+
+pub fn redeem(user: &Pubkey, pool: &LendingPool, shares: u64) -> Instruction {
+    let accounts = event_accounts!(Redeem {
+        user: *user,
+        user_token_account: user_token_account(user, &pool.mint),
+        user_receipt_account: user_receipt_account(user, &pool.mint),
+        pool: find_pool_address(&pool.mint).0,
+        pool_token_account: pool_token_account(&pool.mint),
+        receipt_mint: pool.receipt_mint,
+        token_program: token::ID,
+    });
+    build(accounts, instruction::Redeem { shares }, vec![])
+}
+
+pub fn deposit_collateral(user: &Pubkey, pool: &LendingPool, amount: u64) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(DepositCollateral {
+        user: *user,
+        user_token_account: user_token_account(user, &pool.mint),
+        user_position: find_user_position_address(user, &pool_key).0,
+        obligation: find_obligation_address(user).0,
+        pool: pool_key,
+        pool_token_account: pool_token_account(&pool.mint),
+        receipt_mint: pool.receipt_mint,
+        collateral_vault: pool.collateral_vault,
+        token_program: token::ID,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+    });
+    build(accounts, instruction::DepositCollateral { amount }, vec![])
+}
+
+// 将用户持有的存款凭证转入仓位作为抵押
+pub fn deposit_receipts(user: &Pubkey, pool: &LendingPool, shares: u64) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(DepositReceipts {
+        user: *user,
+        user_receipt_account: user_receipt_account(user, &pool.mint),
+        user_position: find_user_position_address(user, &pool_key).0,
+        obligation: find_obligation_address(user).0,
+        pool: pool_key,
+        collateral_vault: pool.collateral_vault,
+        token_program: token::ID,
+        system_program: system_program::ID,
+    });
+    build(accounts, instruction::DepositReceipts { shares }, vec![])
+}
+
+// 将仓位存款份额以存款凭证形式转回用户，remaining_accounts 由 health_check_accounts 生成（排除本仓位）
+pub fn withdraw_receipts(
+    user: &Pubkey,
+    pool: &LendingPool,
+    shares: u64,
+    remaining_accounts: Vec<AccountMeta>
+) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(WithdrawReceipts {
+        user: *user,
+        user_receipt_account: user_receipt_account(user, &pool.mint),
+        user_position: find_user_position_address(user, &pool_key).0,
+        obligation: find_obligation_address(user).0,
+        pool: pool_key,
+        receipt_mint: pool.receipt_mint,
+        collateral_vault: pool.collateral_vault,
+        token_program: token::ID,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+        price_feed: pool.oracle_feed,
+    });
+    build(accounts, instruction::WithdrawReceipts { shares }, remaining_accounts)
+}
+
+fn withdraw_accounts(user: &Pubkey, pool: &LendingPool) -> accounts::Withdraw {
+    let pool_key = find_pool_address(&pool.mint).0;
+    event_accounts!(Withdraw {
+        user: *user,
+        user_token_account: user_token_account(user, &pool.mint),
+        user_position: find_user_position_address(user, &pool_key).0,
+        obligation: find_obligation_address(user).0,
+        pool: pool_key,
+        pool_token_account: pool_token_account(&pool.mint),
+        receipt_mint: pool.receipt_mint,
+        collateral_vault: pool.collateral_vault,
+        token_program: token::ID,
+        system_program: system_program::ID,
+        price_feed: pool.oracle_feed,
+    })
+}
+
+// remaining_accounts 由 health_check_accounts 生成（排除本仓位）
+pub fn withdraw(user: &Pubkey, pool: &LendingPool, amount: u64, remaining_accounts: Vec<AccountMeta>) -> Instruction {
+    build(withdraw_accounts(user, pool), instruction::Withdraw { amount }, remaining_accounts)
+}
+
+pub fn withdraw_collateral(
+    user: &Pubkey,
+    pool: &LendingPool,
+    amount: u64,
+    remaining_accounts: Vec<AccountMeta>
+) -> Instruction {
+    build(withdraw_accounts(user, pool), instruction::WithdrawCollateral { amount }, remaining_accounts)
+}
+
+pub fn set_collateral_enabled(
+    user: &Pubkey,
+    pool: &LendingPool,
+    enabled: bool,
+    remaining_accounts: Vec<AccountMeta>
+) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(SetCollateralEnabled {
+        user: *user,
+        user_position: find_user_position_address(user, &pool_key).0,
+        obligation: find_obligation_address(user).0,
+        pool: pool_key,
+        price_feed: pool.oracle_feed,
+    });
+    build(accounts, instruction::SetCollateralEnabled { enabled }, remaining_accounts)
+}
+
+pub fn borrow(user: &Pubkey, pool: &LendingPool, amount: u64, remaining_accounts: Vec<AccountMeta>) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(Borrow {
+        user: *user,
+        user_token_account: user_token_account(user, &pool.mint),
+        user_position: find_user_position_address(user, &pool_key).0,
+        obligation: find_obligation_address(user).0,
+        pool: pool_key,
+        pool_token_account: pool_token_account(&pool.mint),
+        token_program: token::ID,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+        rent: sysvar::rent::ID,
+        price_feed: pool.oracle_feed,
+    });
+    build(accounts, instruction::Borrow { amount }, remaining_accounts)
+}
+
+pub fn repay(user: &Pubkey, pool: &LendingPool, amount: u64) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(Repay {
+        user: *user,
+        user_token_account: user_token_account(user, &pool.mint),
+        user_position: find_user_position_address(user, &pool_key).0,
+        obligation: find_obligation_address(user).0,
+        pool: pool_key,
+        pool_token_account: pool_token_account(&pool.mint),
+        token_program: token::ID,
+        system_program: system_program::ID,
+    });
+    build(accounts, instruction::Repay { amount }, vec![])
+}
+
+// remaining_accounts 由 health_check_accounts 生成（排除偿还与没收两个仓位）
+pub fn liquidate(
+    liquidator: &Pubkey,
+    user: &Pubkey,
+    repay_pool: &LendingPool,
+    seize_pool: &LendingPool,
+    repay_amount: u64,
+    remaining_accounts: Vec<AccountMeta>
+) -> Instruction {
+    let repay_pool_key = find_pool_address(&repay_pool.mint).0;
+    let seize_pool_key = find_pool_address(&seize_pool.mint).0;
+    let accounts = event_accounts!(Liquidate {
+        liquidator: *liquidator,
+        liquidator_repay_token_account: user_token_account(liquidator, &repay_pool.mint),
+        liquidator_seize_token_account: user_token_account(liquidator, &seize_pool.mint),
+        user: *user,
+        obligation: find_obligation_address(user).0,
+        repay_position: find_user_position_address(user, &repay_pool_key).0,
+        seize_position: find_user_position_address(user, &seize_pool_key).0,
+        repay_pool: repay_pool_key,
+        repay_pool_token_account: pool_token_account(&repay_pool.mint),
+        seize_pool: seize_pool_key,
+        seize_pool_token_account: pool_token_account(&seize_pool.mint),
+        seize_receipt_mint: seize_pool.receipt_mint,
+        seize_collateral_vault: seize_pool.collateral_vault,
+        token_program: token::ID,
+        system_program: system_program::ID,
+        repay_feed: repay_pool.oracle_feed,
+        seize_feed: seize_pool.oracle_feed,
+    });
+    build(accounts, instruction::Liquidate { repay_amount }, remaining_accounts)
+}
+
+// 同一资金池清算（偿还与没收为同一仓位），remaining_accounts 由 health_check_accounts 生成（排除该仓位）
+pub fn liquidate_same_pool(
+    liquidator: &Pubkey,
+    user: &Pubkey,
+    pool: &LendingPool,
+    repay_amount: u64,
+    remaining_accounts: Vec<AccountMeta>
+) -> Instruction {
+    let pool_key = find_pool_address(&pool.mint).0;
+    let accounts = event_accounts!(LiquidateSamePool {
+        liquidator: *liquidator,
+        liquidator_token_account: user_token_account(liquidator, &pool.mint),
+        user: *user,
+        obligation: find_obligation_address(user).0,
+        user_position: find_user_position_address(user, &pool_key).0,
+        pool: pool_key,
+        pool_token_account: pool_token_account(&pool.mint),
+        receipt_mint: pool.receipt_mint,
+        collateral_vault: pool.collateral_vault,
+        token_program: token::ID,
+        system_program: system_program::ID,
+        price_feed: pool.oracle_feed,
+    });
+    build(accounts, instruction::LiquidateSamePool { repay_amount }, remaining_accounts)
+}
+
+// 闪电贷：flash_borrow 与 flash_repay 需放在同一交易中（flash_borrow 为顶层指令）
+pub fn flash_borrow(user: &Pubkey, pool: &LendingPool, amount: u64) -> Instruction {
+    let accounts = event_accounts!(FlashBorrow {
+        user: *user,
+        destination_token_account: user_token_account(user, &pool.mint),
+        pool: find_pool_address(&pool.mint).0,
+        pool_token_account: pool_token_account(&pool.mint),
+        instructions: sysvar::instructions::ID,
+        token_program: token::ID,
+    });
+    build(accounts, instruction::FlashBorrow { amount }, vec![])
+}
+
+pub fn flash_repay(user: &Pubkey, pool: &LendingPool, amount: u64) -> Instruction {
+    let accounts = event_accounts!(FlashRepay {
+        user: *user,
+        user_token_account: user_token_account(user, &pool.mint),
+        pool: find_pool_address(&pool.mint).0,
+        pool_token_account: pool_token_account(&pool.mint),
+        token_program: token::ID,
+    });
+    build(accounts, instruction::FlashRepay { amount }, vec![])
+}
+
+#[cfg(test)]
+fn test_pool() -> LendingPool {
+    let mint = Pubkey::new_unique();
+    LendingPool {
+        mint,
+        oracle_feed: Pubkey::new_unique(),
+        receipt_mint: find_receipt_mint_address(&find_pool_address(&mint).0).0,
+        ..LendingPool::default()
+    }
+}
+
+#[test]
+fn test_borrow_instruction() {
+    use anchor_lang::Discriminator;
+
+    let user = Pubkey::new_unique();
+    let pool = test_pool();
+    let other = test_pool();
+    let pool_key = find_pool_address(&pool.mint).0;
+    let position = find_user_position_address(&user, &pool_key).0;
+    let other_accounts = ObligationPositionAccounts::new(&user, &other);
+
+    // 义务账户中包含本仓位与另一资金池的仓位
+    let mut obligation = Obligation { owner: user, position_count: 2, ..Obligation::default() };
+    obligation.positions[0] = other_accounts.position;
+    obligation.positions[1] = position;
+
+    let remaining = health_check_accounts(&obligation, &[position], &[other_accounts]).unwrap();
+    assert_eq!(remaining.len(), 3);
+    assert_eq!(remaining[0].pubkey, other_accounts.pool);
+    assert_eq!(remaining[2].pubkey, other.oracle_feed);
+
+    let ix = borrow(&user, &pool, 1_000, remaining);
+    assert_eq!(ix.program_id, ID);
+    assert_eq!(ix.data[..8], instruction::Borrow::DISCRIMINATOR);
+    assert_eq!(ix.data[8..], 1_000u64.to_le_bytes());
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == position && meta.is_writable));
+    assert!(ix.accounts.iter().any(|meta| meta.pubkey == user && meta.is_signer));
+    assert_eq!(ix.accounts.last().unwrap().pubkey, other.oracle_feed);
+
+    // 缺少义务账户中的仓位时报错
+    assert!(health_check_accounts(&obligation, &[position], &[]).is_err());
+    assert_eq!(bad_debt_accounts(&obligation, &[position], &[other_accounts]).unwrap().len(), 1);
+}
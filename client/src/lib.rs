@@ -0,0 +1,28 @@
+// lending_pool 链下客户端
+// - pda：资金池、仓位、义务账户等 PDA 及代币账户地址推导
+// - instructions：按高层参数构造各条指令（自动填充全部账户）
+// - state：账户反序列化，以及复用链上数学计算余额、利率与健康因子
+pub mod instructions;
+pub mod pda;
+pub mod state;
+
+pub use lending_pool::{ health, math, ID };
+pub use lending_pool::model::{
+    InitPoolParams,
+    InterestRateModel,
+    LendingError,
+    LendingPool,
+    Obligation,
+    OracleType,
+    PoolConfigParams,
+    PoolStateSnapshot,
+    RateKink,
+    UserPosition,
+    PAUSE_ALL,
+    PAUSE_BORROW,
+    PAUSE_DEPOSIT,
+    PAUSE_FLASH_LOAN,
+    PAUSE_LIQUIDATE,
+    PAUSE_REPAY,
+    PAUSE_WITHDRAW,
+};
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::metadata::mpl_token_metadata;
+
+use lending_pool::ID;
+
+// 资金池：[b"lending_pool", mint]
+pub fn find_pool_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"lending_pool", mint.as_ref()], &ID)
+}
+
+// 存款凭证 Mint：[b"receipt_mint", pool]
+pub fn find_receipt_mint_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt_mint", pool.as_ref()], &ID)
+}
+
+// 存款凭证的 Metaplex 元数据账户（由元数据程序推导）
+pub fn find_receipt_metadata_address(receipt_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), receipt_mint.as_ref()],
+        &mpl_token_metadata::ID
+    )
+}
+
+// 保险基金代币账户：[b"insurance_vault", pool]
+pub fn find_insurance_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"insurance_vault", pool.as_ref()], &ID)
+}
+
+// 仓位存款凭证托管账户：[b"collateral_vault", pool]
+pub fn find_collateral_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"collateral_vault", pool.as_ref()], &ID)
+}
+
+// 用户仓位：[b"user_position", user, pool]
+pub fn find_user_position_address(user: &Pubkey, pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user_position", user.as_ref(), pool.as_ref()], &ID)
+}
+
+// 义务账户：[b"obligation", user]
+pub fn find_obligation_address(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"obligation", user.as_ref()], &ID)
+}
+
+// event-cpi 模式下的事件权限 PDA：[b"__event_authority"]
+pub fn find_event_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &ID)
+}
+
+// 资金池持有的代币账户（资金池 PDA 的 ATA）
+pub fn pool_token_account(mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&find_pool_address(mint).0, mint)
+}
+
+// 用户的代币账户（ATA）
+pub fn user_token_account(user: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(user, mint)
+}
+
+// 用户的存款凭证账户（凭证 Mint 的 ATA）
+pub fn user_receipt_account(user: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let receipt_mint = find_receipt_mint_address(&find_pool_address(mint).0).0;
+    get_associated_token_address(user, &receipt_mint)
+}
+
+#[test]
+fn test_pda_addresses() {
+    let mint = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let (pool, bump) = find_pool_address(&mint);
+
+    // 与链上 seeds 一致
+    let expected = Pubkey::create_program_address(&[b"lending_pool", mint.as_ref(), &[bump]], &ID).unwrap();
+    assert_eq!(pool, expected);
+
+    // 不同用户、不同资金池的仓位互不相同
+    let position = find_user_position_address(&user, &pool).0;
+    assert_ne!(position, find_user_position_address(&Pubkey::new_unique(), &pool).0);
+    assert_ne!(position, find_user_position_address(&user, &Pubkey::new_unique()).0);
+
+    assert_eq!(pool_token_account(&mint), get_associated_token_address(&pool, &mint));
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::{ Pubkey, Result };
+use anchor_lang::AccountDeserialize;
+
+use lending_pool::health::{ HealthFactor, HealthStatus };
+use lending_pool::math::{ Decimal, Rounding };
+use lending_pool::model::{ LendingPool, PoolStateSnapshot, UserPosition };
+use lending_pool::price::read_oracle_price_data;
+use lending_pool::utils::{
+    accrue_pool_interest,
+    borrowed_amount,
+    collateral_amount,
+    deposited_amount,
+    pool_state_snapshot,
+    scaled_to_amount,
+    ObligationValues,
+};
+
+// 反序列化程序账户（校验 8 字节 discriminator），如 LendingPool、UserPosition、Obligation
+pub fn deserialize_account<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+// 将资金池利息结算到 now（与链上 accrue_interest 相同的计算），返回新的资金池状态
+pub fn pool_at(pool: &LendingPool, now: i64) -> Result<LendingPool> {
+    let mut pool = pool.clone();
+    accrue_pool_interest(&mut pool, now)?;
+    Ok(pool)
+}
+
+// 资金池指数、利用率与年化利率（18 位小数），与事件中记录的状态一致
+pub fn pool_state(pool: &LendingPool) -> Result<PoolStateSnapshot> {
+    pool_state_snapshot(pool)
+}
+
+// 按资金池配置解析 feed 账户数据得到价格
+pub fn oracle_price(pool: &LendingPool, feed_owner: &Pubkey, feed_data: &[u8], now: i64) -> Result<Decimal> {
+    read_oracle_price_data(pool, feed_owner, feed_data, now)
+}
+
+// 存款凭证（cToken）可兑换的代币数量（向下取整）
+pub fn receipt_value(pool: &LendingPool, shares: u64) -> Result<u64> {
+    scaled_to_amount(shares, pool.liquidity_index, Rounding::Down)
+}
+
+// 仓位当前余额（含已累计利息，传入的资金池应先用 pool_at 结算到当前时间）
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PositionBalances {
+    pub deposited: u64, // 存款（向下取整）
+    pub borrowed: u64, // 借款（向上取整）
+    pub collateral: u64, // 计入抵押的存款
+}
+
+pub fn position_balances(position: &UserPosition, pool: &LendingPool) -> Result<PositionBalances> {
+    Ok(PositionBalances {
+        deposited: deposited_amount(position, pool)?,
+        borrowed: borrowed_amount(position, pool)?,
+        collateral: collateral_amount(position, pool)?,
+    })
+}
+
+// 义务账户整体健康状况
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ObligationHealth {
+    pub borrow_health: HealthFactor, // 按最高借款比例加权（链上借款/取款检查使用）
+    pub liquidation_health: HealthFactor, // 按清算阈值加权（链上清算检查使用）
+    pub status: HealthStatus,
+}
+
+// 按链上相同的估值方式汇总义务账户中的所有仓位：(资金池, 用户仓位, 价格)
+pub fn obligation_health(positions: &[(&LendingPool, &UserPosition, Decimal)]) -> Result<ObligationHealth> {
+    let mut values = ObligationValues::default();
+    for (pool, position, price) in positions {
+        values.add_position(collateral_amount(position, pool)?, borrowed_amount(position, pool)?, pool, *price)?;
+    }
+
+    Ok(ObligationHealth {
+        borrow_health: values.borrow_health()?,
+        liquidation_health: values.liquidation_health()?,
+        status: values.status()?,
+    })
+}
+
+#[test]
+fn test_deserialize_account() {
+    use anchor_lang::AccountSerialize;
+
+    let pool = LendingPool { mint: Pubkey::new_unique(), total_supply: 1_000, ..LendingPool::default() };
+    let mut data = Vec::new();
+    pool.try_serialize(&mut data).unwrap();
+
+    let decoded: LendingPool = deserialize_account(&data).unwrap();
+    assert_eq!(decoded.mint, pool.mint);
+    assert_eq!(decoded.total_supply, 1_000);
+
+    // discriminator 不匹配的账户被拒绝
+    assert!(deserialize_account::<UserPosition>(&data).is_err());
+}
+
+#[test]
+fn test_obligation_health() {
+    use lending_pool::math::Rate;
+
+    let one = Rate::one().to_scaled_val();
+    let pool = LendingPool {
+        decimals: 6,
+        loan_to_value: 75,
+        liquidation_threshold: 80,
+        liquidity_index: one,
+        borrow_index: one,
+        ..LendingPool::default()
+    };
+    // 抵押 100，借款 78：超过 75% 借款上限，但未达 80% 清算阈值
+    let collateral = UserPosition { scaled_deposited_amount: 100_000_000, collateral_enabled: true, ..UserPosition::default() };
+    let debt = UserPosition { scaled_borrowed_amount: 78_000_000, ..UserPosition::default() };
+
+    let health = obligation_health(&[(&pool, &collateral, Decimal::one()), (&pool, &debt, Decimal::one())]).unwrap();
+    assert_eq!(health.status, HealthStatus::BorrowLimited);
+    assert!(!health.borrow_health.is_healthy());
+    assert!(health.liquidation_health.is_healthy());
+
+    // 无债务时健康因子为无穷大
+    let health = obligation_health(&[(&pool, &collateral, Decimal::one())]).unwrap();
+    assert_eq!(health.status, HealthStatus::Healthy);
+    assert!(health.borrow_health.is_infinite());
+
+    assert_eq!(position_balances(&debt, &pool).unwrap().borrowed, 78_000_000);
+    assert_eq!(receipt_value(&pool, 1_000).unwrap(), 1_000);
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::math::{ Decimal, Rate, TryDiv };
+
+// 健康因子：加权抵押价值 / 债务价值，18 位小数（1.0 = Rate::one()），向下取整
+// 无债务时为无穷大（u128::MAX），超出 u128 范围时同样按无穷大处理
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HealthFactor(pub u128);
+
+impl HealthFactor {
+    pub const INFINITE: Self = Self(u128::MAX);
+
+    pub fn is_infinite(&self) -> bool {
+        *self == Self::INFINITE
+    }
+
+    // 不低于 1.0 视为健康
+    pub fn is_healthy(&self) -> bool {
+        self.0 >= Rate::one().to_scaled_val()
+    }
+}
+
+// 仓位健康状态
+// - Healthy：按最高借款比例加权仍覆盖债务，可继续借款
+// - BorrowLimited：超过最高借款比例但未达清算阈值，不能新增借款或取出抵押，但不会被清算
+// - Liquidatable：按清算阈值加权已不足以覆盖债务
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthStatus {
+    Healthy,
+    BorrowLimited,
+    Liquidatable,
+}
+
+impl HealthStatus {
+    // borrow_health 按最高借款比例加权，liquidation_health 按清算阈值加权
+    pub fn new(borrow_health: HealthFactor, liquidation_health: HealthFactor) -> Self {
+        if !liquidation_health.is_healthy() {
+            HealthStatus::Liquidatable
+        } else if !borrow_health.is_healthy() {
+            HealthStatus::BorrowLimited
+        } else {
+            HealthStatus::Healthy
+        }
+    }
+}
+
+// 计算健康因子：加权抵押价值 / 债务价值
+pub fn calculate_health_factor(weighted_collateral_value: Decimal, debt_value: Decimal) -> Result<HealthFactor> {
+    if debt_value.is_zero() {
+        return Ok(HealthFactor::INFINITE);
+    }
+
+    let health_factor = weighted_collateral_value.try_div(debt_value)?;
+
+    Ok(health_factor.to_scaled_val().map_or(HealthFactor::INFINITE, HealthFactor))
+}
+
+#[test]
+fn test_calculate_health_factor() {
+    let one = Rate::one().to_scaled_val();
+
+    // 无债务时为无穷大，无论是否有抵押
+    assert_eq!(calculate_health_factor(Decimal::from(100u64), Decimal::zero()).unwrap(), HealthFactor::INFINITE);
+    assert!(calculate_health_factor(Decimal::zero(), Decimal::zero()).unwrap().is_infinite());
+
+    // 150 / 100 = 1.5；无抵押时为 0
+    let health = calculate_health_factor(Decimal::from(150u64), Decimal::from(100u64)).unwrap();
+    assert_eq!(health, HealthFactor(one * 3 / 2));
+    assert!(health.is_healthy());
+    assert_eq!(calculate_health_factor(Decimal::zero(), Decimal::from(100u64)).unwrap(), HealthFactor(0));
+
+    // 保留小数精度：99.99 / 100 不健康
+    let value = Decimal::from(9_999u64).try_div(100u64).unwrap();
+    assert!(!calculate_health_factor(value, Decimal::from(100u64)).unwrap().is_healthy());
+}
+
+#[test]
+fn test_health_status() {
+    let one = Rate::one().to_scaled_val();
+
+    assert_eq!(HealthStatus::new(HealthFactor::INFINITE, HealthFactor::INFINITE), HealthStatus::Healthy);
+    assert_eq!(HealthStatus::new(HealthFactor(one), HealthFactor(one)), HealthStatus::Healthy);
+    // 超过最高借款比例、未达清算阈值
+    assert_eq!(HealthStatus::new(HealthFactor(one - 1), HealthFactor(one)), HealthStatus::BorrowLimited);
+    assert_eq!(HealthStatus::new(HealthFactor(one / 2), HealthFactor(one - 1)), HealthStatus::Liquidatable);
+}
@@ -1,9 +1,23 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Transfer};
+use anchor_spl::metadata::{
+    create_metadata_accounts_v3,
+    mpl_token_metadata::types::DataV2,
+    CreateMetadataAccountsV3,
+};
+use anchor_spl::token::{self, Burn, MintTo, Transfer};
 
-mod model;
-mod utils;
+// 状态、数学与估值模块对链下客户端公开（lending-pool-client 复用同一套计算）
+pub mod health;
+pub mod math;
+pub mod model;
+pub mod oracle;
+pub mod price;
+pub mod rate;
+pub mod utils;
+use math::{ Rate, Rounding };
 use model::*;
+use price::*;
+use rate::*;
 use utils::*;
 
 declare_id!("7D5MCa7qRv8wpbnycWVqDfgo8pZj5v2ghqvD2vy2jLiH");
@@ -13,45 +27,544 @@ pub mod lending_pool {
     use super::*;
 
     // 初始化资金池
-    pub fn initialize_pool(
-        ctx: Context<InitializePool>,
-        mint: Pubkey,
-        decimals: u8,
-        reserve_factor: u8,
-        collateral_factor: u8,
-        base_rate: u64,
-    ) -> Result<()> {
+    pub fn initialize_pool(ctx: Context<InitializePool>, params: InitPoolParams) -> Result<()> {
         let pool = &mut ctx.accounts.pool;
 
-        // 设置初始参数
-        pool.mint = mint;
-        pool.decimals = decimals;
-        pool.reserve_factor = reserve_factor;
-        pool.collateral_factor = collateral_factor;
-        pool.base_rate = base_rate;
+        // 设置初始参数（Mint 与精度直接取自 Mint 账户）
+        pool.mint = ctx.accounts.mint.key();
+        pool.decimals = ctx.accounts.mint.decimals;
+        pool.reserve_factor = params.reserve_factor;
+        pool.loan_to_value = params.loan_to_value;
+        pool.liquidation_threshold = params.liquidation_threshold;
+        pool.rate_model = params.rate_model;
+        pool.liquidation_bonus = params.liquidation_bonus;
+        pool.close_factor = params.close_factor;
+        pool.max_staleness = params.max_staleness;
+        pool.oracle_type = params.oracle_type;
+        pool.oracle_fixed_price = params.oracle_fixed_price;
+        pool.treasury = params.treasury;
+        pool.fee_authority = params.fee_authority;
+        pool.flash_loan_fee_bps = params.flash_loan_fee_bps;
+        pool.supply_cap = params.supply_cap;
+        pool.borrow_cap = params.borrow_cap;
+        pool.pause_guardian = params.pause_guardian;
+        pool.insurance_fund_factor = params.insurance_fund_factor;
+        pool.bump = ctx.bumps.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.oracle_program = ctx.accounts.feed_program.key();
+        pool.oracle_feed = ctx.accounts.price_feed.key();
+        validate_pool_config(pool)?;
 
         // 初始化全局状态
         pool.total_supply = 0;
         pool.total_borrowed = 0;
-        pool.liquidity_index = 1_000_000_000; // 初始流动性指数
-        pool.borrow_index = 1_000_000_000; // 初始借款指数
+        pool.accumulated_protocol_fees = 0;
+        pool.liquidity_index = Rate::one().to_scaled_val(); // 初始流动性指数
+        pool.borrow_index = Rate::one().to_scaled_val(); // 初始借款指数
         pool.last_update_time = Clock::get()?.unix_timestamp;
+        pool.receipt_mint = ctx.accounts.receipt_mint.key();
+        pool.insurance_vault = ctx.accounts.insurance_vault.key();
+        pool.collateral_vault = ctx.accounts.collateral_vault.key();
+
+        // 为存款凭证创建 Metaplex 元数据（Mint 权限由资金池 PDA 签名）
+        let mint = pool.mint;
+        let bump = pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                CreateMetadataAccountsV3 {
+                    metadata: ctx.accounts.receipt_metadata.to_account_info(),
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    mint_authority: ctx.accounts.pool.to_account_info(),
+                    payer: ctx.accounts.authority.to_account_info(),
+                    update_authority: ctx.accounts.authority.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            DataV2 {
+                name: params.receipt_name,
+                symbol: params.receipt_symbol,
+                uri: params.receipt_uri,
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            true, // 元数据可由管理员更新
+            true, // 管理员已签名
+            None,
+        )?;
+
+        // 记录事件
+        emit_event!(ctx, PoolInitialized {
+            pool: ctx.accounts.pool.key(),
+            mint: ctx.accounts.mint.key(),
+            authority: ctx.accounts.authority.key(),
+            receipt_mint: ctx.accounts.receipt_mint.key(),
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         Ok(())
     }
 
-    // 存款
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    // 更新资金池参数（仅管理员）
+    pub fn update_pool_config(ctx: Context<UpdatePoolConfig>, params: PoolConfigParams) -> Result<()> {
+        // 1. 按旧参数结算此前的利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 更新参数
+        let pool = &mut ctx.accounts.pool;
+        if let Some(reserve_factor) = params.reserve_factor {
+            pool.reserve_factor = reserve_factor;
+        }
+        if let Some(loan_to_value) = params.loan_to_value {
+            pool.loan_to_value = loan_to_value;
+        }
+        if let Some(liquidation_threshold) = params.liquidation_threshold {
+            pool.liquidation_threshold = liquidation_threshold;
+        }
+        if let Some(liquidation_bonus) = params.liquidation_bonus {
+            pool.liquidation_bonus = liquidation_bonus;
+        }
+        if let Some(close_factor) = params.close_factor {
+            pool.close_factor = close_factor;
+        }
+        if let Some(oracle_program) = params.oracle_program {
+            pool.oracle_program = oracle_program;
+        }
+        if let Some(oracle_feed) = params.oracle_feed {
+            pool.oracle_feed = oracle_feed;
+        }
+        if let Some(max_staleness) = params.max_staleness {
+            pool.max_staleness = max_staleness;
+        }
+        if let Some(oracle_type) = params.oracle_type {
+            pool.oracle_type = oracle_type;
+        }
+        if let Some(oracle_fixed_price) = params.oracle_fixed_price {
+            pool.oracle_fixed_price = oracle_fixed_price;
+        }
+        if let Some(treasury) = params.treasury {
+            pool.treasury = treasury;
+        }
+        if let Some(fee_authority) = params.fee_authority {
+            pool.fee_authority = fee_authority;
+        }
+        if let Some(flash_loan_fee_bps) = params.flash_loan_fee_bps {
+            pool.flash_loan_fee_bps = flash_loan_fee_bps;
+        }
+        if let Some(supply_cap) = params.supply_cap {
+            pool.supply_cap = supply_cap;
+        }
+        if let Some(borrow_cap) = params.borrow_cap {
+            pool.borrow_cap = borrow_cap;
+        }
+        if let Some(pause_guardian) = params.pause_guardian {
+            pool.pause_guardian = pause_guardian;
+        }
+        if let Some(insurance_fund_factor) = params.insurance_fund_factor {
+            pool.insurance_fund_factor = insurance_fund_factor;
+        }
+        validate_pool_config(pool)?;
+
+        // 3. 记录事件
+        emit_event!(ctx, PoolConfigUpdated {
+            pool: pool.key(),
+            authority: ctx.accounts.authority.key(),
+            reserve_factor: pool.reserve_factor,
+            loan_to_value: pool.loan_to_value,
+            liquidation_threshold: pool.liquidation_threshold,
+            liquidation_bonus: pool.liquidation_bonus,
+            close_factor: pool.close_factor,
+            oracle_program: pool.oracle_program,
+            oracle_feed: pool.oracle_feed,
+            max_staleness: pool.max_staleness,
+            oracle_type: pool.oracle_type,
+            oracle_fixed_price: pool.oracle_fixed_price,
+            treasury: pool.treasury,
+            fee_authority: pool.fee_authority,
+            flash_loan_fee_bps: pool.flash_loan_fee_bps,
+            supply_cap: pool.supply_cap,
+            borrow_cap: pool.borrow_cap,
+            pause_guardian: pool.pause_guardian,
+            insurance_fund_factor: pool.insurance_fund_factor,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 更新利率模型（仅管理员）
+    pub fn update_rate_model(ctx: Context<UpdatePoolConfig>, rate_model: InterestRateModel) -> Result<()> {
+        // 1. 按旧模型结算此前的利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 更新模型
+        let pool = &mut ctx.accounts.pool;
+        validate_rate_model(&rate_model)?;
+        pool.rate_model = rate_model;
+
+        // 3. 记录事件
+        emit_event!(ctx, RateModelUpdated {
+            pool: pool.key(),
+            authority: ctx.accounts.authority.key(),
+            rate_model,
+            borrow_rate: calculate_borrow_rate(pool)?.to_scaled_val(),
+            supply_rate: calculate_supply_rate(pool)?.to_scaled_val(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 提取协议手续费：按保险基金比例转入保险基金，其余转入金库账户（管理员或手续费管理员）
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        // 1. 结算截至当前的手续费
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 最多提取资金池中现有的代币
+        let pool = &mut ctx.accounts.pool;
+        let amount = pool.accumulated_protocol_fees.min(ctx.accounts.pool_token_account.amount);
+        require!(amount > 0, LendingError::NoProtocolFees);
+        pool.accumulated_protocol_fees -= amount;
+        let insurance_amount = insurance_fund_share(pool, amount)?;
+
+        // 3. 由资金池 PDA 签名，将手续费分别转入保险基金与金库
+        let mint = pool.mint;
+        let bump = pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        if insurance_amount > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.pool_token_account.to_account_info(),
+                        to: ctx.accounts.insurance_vault.to_account_info(),
+                        authority: ctx.accounts.pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                insurance_amount,
+            )?;
+        }
+        if amount > insurance_amount {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.pool_token_account.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                        authority: ctx.accounts.pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount - insurance_amount,
+            )?;
+        }
+
+        // 4. 记录事件
+        emit_event!(ctx, ProtocolFeesCollected {
+            pool: ctx.accounts.pool.key(),
+            authority: ctx.accounts.authority.key(),
+            treasury: ctx.accounts.treasury.key(),
+            amount,
+            insurance_amount,
+            remaining_fees: ctx.accounts.pool.accumulated_protocol_fees,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 核销坏账（仅管理员）：用户义务账户中已无任何存款时，将剩余债务从资金池中移除
+    // 损失先由保险基金弥补，不足部分按比例降低流动性指数由存款人分摊
+    // remaining_accounts 为义务账户中其余的用户仓位，顺序与义务账户一致
+    pub fn settle_bad_debt<'info>(ctx: Context<'_, '_, '_, 'info, SettleBadDebt<'info>>) -> Result<()> {
         // 1. 更新全局利息
-        accrue_interest(&mut ctx.accounts.pool)?;
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 只能核销已无存款可供清算的仓位（未启用抵押的存款无法没收，不阻止核销）
+        require!(
+            !has_seizable_deposits(&ctx.accounts.user_position),
+            LendingError::ObligationHasDeposits
+        );
+        require_no_deposits(
+            &ctx.accounts.obligation,
+            ctx.accounts.user_position.key(),
+            ctx.remaining_accounts
+        )?;
 
-        // 2. 更新用户仓位
+        // 3. 清空仓位债务
+        let pool = &ctx.accounts.pool;
         let user_position = &mut ctx.accounts.user_position;
-        user_position.deposited_amount = user_position
-            .deposited_amount
+        let bad_debt = borrowed_amount(user_position, pool)?;
+        require!(bad_debt > 0, LendingError::NoBadDebt);
+        user_position.scaled_borrowed_amount = 0;
+        update_position_snapshot(user_position, ctx.accounts.user.key(), pool.key(), pool)?;
+        release_position(&mut ctx.accounts.obligation, user_position.key(), user_position);
+
+        // 4. 先由保险基金弥补，剩余损失由存款人分摊
+        let insurance_covered = bad_debt.min(ctx.accounts.insurance_vault.amount);
+        let pool = &mut ctx.accounts.pool;
+        pool.total_borrowed = pool.total_borrowed.saturating_sub(bad_debt);
+        let socialized_loss = socialize_loss(pool, bad_debt - insurance_covered)?;
+
+        // 5. 保险基金将弥补的金额转入资金池（由资金池 PDA 签名）
+        if insurance_covered > 0 {
+            let mint = pool.mint;
+            let bump = pool.bump;
+            let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.insurance_vault.to_account_info(),
+                        to: ctx.accounts.pool_token_account.to_account_info(),
+                        authority: ctx.accounts.pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                insurance_covered,
+            )?;
+        }
+
+        // 6. 记录事件
+        emit_event!(ctx, BadDebtSettled {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            bad_debt,
+            insurance_covered,
+            socialized_loss,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 设置暂停的操作（PAUSE_* 位标志）：暂停守护者只能新增暂停，管理员可任意设置
+    pub fn set_paused_operations(ctx: Context<SetPausedOperations>, paused_operations: u8) -> Result<()> {
+        require!(paused_operations & !PAUSE_ALL == 0, LendingError::InvalidPoolConfig);
+
+        let pool = &mut ctx.accounts.pool;
+        let previous_paused_operations = pool.paused_operations;
+        if ctx.accounts.authority.key() != pool.authority {
+            require!(
+                paused_operations & previous_paused_operations == previous_paused_operations,
+                LendingError::Unauthorized
+            );
+        }
+        pool.paused_operations = paused_operations;
+
+        emit_event!(ctx, PausedOperationsUpdated {
+            pool: pool.key(),
+            authority: ctx.accounts.authority.key(),
+            previous_paused_operations,
+            paused_operations,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 提名新管理员（传入默认地址可撤销提名）
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.pending_authority = new_authority;
+
+        emit_event!(ctx, AuthorityProposed {
+            pool: pool.key(),
+            authority: pool.authority,
+            pending_authority: new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 被提名的管理员接受权限
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let previous_authority = pool.authority;
+        pool.authority = ctx.accounts.pending_authority.key();
+        pool.pending_authority = Pubkey::default();
+
+        emit_event!(ctx, AuthorityAccepted {
+            pool: pool.key(),
+            previous_authority,
+            authority: pool.authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 存款：按当前流动性指数铸造存款凭证（cToken），凭证可自由转让
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_DEPOSIT)?;
+
+        // 1. 更新全局利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 检查供应上限，按当前流动性指数换算凭证份额（向下取整）
+        let pool = &mut ctx.accounts.pool;
+        check_supply_cap(pool, amount)?;
+        let shares = amount_to_scaled(amount, pool.liquidity_index, Rounding::Down)?;
+        require!(shares > 0, LendingError::AmountTooSmall);
+
+        // 3. 更新资金池
+        pool.total_supply = pool
+            .total_supply
             .checked_add(amount)
             .ok_or(LendingError::MathOverflow)?;
 
+        // 4. 转移代币到资金池
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        // 5. 由资金池 PDA 签名铸造存款凭证
+        let mint = ctx.accounts.pool.mint;
+        let bump = ctx.accounts.pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.user_receipt_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            shares,
+        )?;
+
+        // 6. 记录事件
+        emit_event!(ctx, DepositEvent {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            scaled_amount: shares,
+            to_position: false,
+            collateral: false,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 赎回：销毁存款凭证，按当前流动性指数取回代币
+    pub fn redeem(ctx: Context<Redeem>, shares: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_WITHDRAW)?;
+
+        // 1. 更新全局利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 按当前流动性指数换算可取回数量（向下取整）
+        let pool = &mut ctx.accounts.pool;
+        let amount = scaled_to_amount(shares, pool.liquidity_index, Rounding::Down)?;
+        require!(amount > 0, LendingError::AmountTooSmall);
+
+        // 3. 检查可用流动性并更新资金池
+        let available_liquidity = pool.total_supply
+            .checked_sub(pool.total_borrowed)
+            .ok_or(LendingError::MathOverflow)?;
+        require!(amount <= available_liquidity, LendingError::InsufficientLiquidity);
+        pool.total_supply -= amount;
+
+        // 4. 销毁存款凭证
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    from: ctx.accounts.user_receipt_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        // 5. 由资金池 PDA 签名，将代币转回用户账户
+        let mint = ctx.accounts.pool.mint;
+        let bump = ctx.accounts.pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.pool_token_account.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        // 6. 记录事件
+        emit_event!(ctx, WithdrawEvent {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            scaled_amount: shares,
+            from_position: false,
+            collateral: false,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 存入抵押品（存款并启用抵押）
+    pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_DEPOSIT)?;
+
+        // 1. 更新全局利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 检查供应上限，按当前流动性指数换算存款份额并启用抵押
+        let pool = &ctx.accounts.pool;
+        check_supply_cap(pool, amount)?;
+        let scaled_amount = amount_to_scaled(amount, pool.liquidity_index, Rounding::Down)?;
+        let user_position = &mut ctx.accounts.user_position;
+        user_position.scaled_deposited_amount = user_position
+            .scaled_deposited_amount
+            .checked_add(scaled_amount)
+            .ok_or(LendingError::MathOverflow)?;
+        user_position.collateral_enabled = true;
+        update_position_snapshot(user_position, ctx.accounts.user.key(), pool.key(), pool)?;
+        register_position(&mut ctx.accounts.obligation, ctx.accounts.user.key(), user_position.key())?;
+
         // 3. 更新资金池
         let pool = &mut ctx.accounts.pool;
         pool.total_supply = pool
@@ -72,11 +585,253 @@ pub mod lending_pool {
             amount,
         )?;
 
+        // 5. 由资金池 PDA 签名，将等量存款凭证铸造到托管账户（仓位存款份额与托管凭证一一对应）
+        let mint = ctx.accounts.pool.mint;
+        let bump = ctx.accounts.pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    to: ctx.accounts.collateral_vault.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            scaled_amount,
+        )?;
+
+        // 6. 记录事件
+        emit_event!(ctx, DepositEvent {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            scaled_amount,
+            to_position: true,
+            collateral: true,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 存入存款凭证作为抵押：凭证转入托管账户，仓位按凭证数量增加存款份额（总供应不变）
+    pub fn deposit_receipts(ctx: Context<DepositReceipts>, shares: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_DEPOSIT)?;
+        require!(shares > 0, LendingError::AmountTooSmall);
+
+        // 1. 更新全局利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 凭证份额即仓位存款份额，直接计入仓位并启用抵押
+        let pool = &ctx.accounts.pool;
+        let user_position = &mut ctx.accounts.user_position;
+        user_position.scaled_deposited_amount = user_position
+            .scaled_deposited_amount
+            .checked_add(shares)
+            .ok_or(LendingError::MathOverflow)?;
+        user_position.collateral_enabled = true;
+        update_position_snapshot(user_position, ctx.accounts.user.key(), pool.key(), pool)?;
+        register_position(&mut ctx.accounts.obligation, ctx.accounts.user.key(), user_position.key())?;
+
+        // 3. 将凭证转入托管账户
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_receipt_account.to_account_info(),
+                    to: ctx.accounts.collateral_vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            shares,
+        )?;
+
+        // 4. 记录事件
+        emit_event!(ctx, CollateralReceiptsDeposited {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            shares,
+            amount: scaled_to_amount(shares, ctx.accounts.pool.liquidity_index, Rounding::Down)?,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 转出存款凭证：仓位存款份额以凭证形式从托管账户转回用户（总供应不变，需健康检查）
+    pub fn withdraw_receipts<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawReceipts<'info>>,
+        shares: u64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_WITHDRAW)?;
+        require!(shares > 0, LendingError::AmountTooSmall);
+
+        // 1. 更新全局利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 转出前检查（份额余额 + 健康因子）
+        check_before_withdraw_receipts(&ctx, shares)?;
+
+        // 3. 扣减仓位存款份额
+        let pool = &ctx.accounts.pool;
+        let user_position = &mut ctx.accounts.user_position;
+        user_position.scaled_deposited_amount -= shares;
+        update_position_snapshot(user_position, ctx.accounts.user.key(), pool.key(), pool)?;
+        release_position(&mut ctx.accounts.obligation, user_position.key(), user_position);
+
+        // 4. 由资金池 PDA 签名，将凭证从托管账户转给用户
+        let mint = ctx.accounts.pool.mint;
+        let bump = ctx.accounts.pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.collateral_vault.to_account_info(),
+                    to: ctx.accounts.user_receipt_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            shares,
+        )?;
+
         // 5. 记录事件
-        emit!(LendingEvent {
-            event_type: 0, // 0=存款
+        emit_event!(ctx, CollateralReceiptsWithdrawn {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            shares,
+            amount: scaled_to_amount(shares, ctx.accounts.pool.liquidity_index, Rounding::Down)?,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 取出抵押品（仅限已启用抵押的仓位，始终做健康检查）
+    pub fn withdraw_collateral<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.user_position.collateral_enabled,
+            LendingError::CollateralNotEnabled
+        );
+
+        withdraw(ctx, amount)
+    }
+
+    // 启用/停用仓位抵押
+    pub fn set_collateral_enabled<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetCollateralEnabled<'info>>,
+        enabled: bool,
+    ) -> Result<()> {
+        // 1. 更新全局利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 停用抵押前，确认剩余仓位仍然健康
+        let health_factor = if enabled { None } else { check_before_disable_collateral(&ctx)? };
+
+        // 3. 更新仓位抵押状态
+        let pool = &ctx.accounts.pool;
+        let user_position = &mut ctx.accounts.user_position;
+        user_position.collateral_enabled = enabled;
+        update_position_snapshot(user_position, ctx.accounts.user.key(), pool.key(), pool)?;
+
+        // 4. 记录事件
+        emit_event!(ctx, CollateralToggledEvent {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            enabled,
+            health_factor,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 取款
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_WITHDRAW)?;
+
+        // 1. 更新全局利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 取款前检查（可用流动性 + 健康因子）
+        check_before_withdraw(&ctx, amount)?;
+
+        // 3. 扣减存款份额（向上取整，取完时清零）
+        let pool = &ctx.accounts.pool;
+        let user_position = &mut ctx.accounts.user_position;
+        let scaled_amount = scaled_withdraw_amount(user_position, pool, amount)?;
+        user_position.scaled_deposited_amount = user_position
+            .scaled_deposited_amount
+            .checked_sub(scaled_amount)
+            .ok_or(LendingError::MathOverflow)?;
+        update_position_snapshot(user_position, ctx.accounts.user.key(), pool.key(), pool)?;
+        release_position(&mut ctx.accounts.obligation, user_position.key(), user_position);
+
+        // 4. 更新资金池
+        let pool = &mut ctx.accounts.pool;
+        pool.total_supply = pool
+            .total_supply
+            .checked_sub(amount)
+            .ok_or(LendingError::MathOverflow)?;
+
+        // 5. 由资金池 PDA 签名，销毁托管账户中对应份额的存款凭证，并将代币转回用户账户
+        let mint = pool.mint;
+        let bump = pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        token::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    from: ctx.accounts.collateral_vault.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            scaled_amount,
+        )?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.pool_token_account.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
             amount,
+        )?;
+
+        // 6. 记录事件
+        emit_event!(ctx, WithdrawEvent {
+            pool: ctx.accounts.pool.key(),
             user: ctx.accounts.user.key(),
+            amount,
+            scaled_amount,
+            from_position: true,
+            collateral: ctx.accounts.user_position.collateral_enabled,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
@@ -84,19 +839,30 @@ pub mod lending_pool {
     }
 
     // 借款
-    pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+    pub fn borrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, Borrow<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_BORROW)?;
+
         // 1. 更新全局利息
-        accrue_interest(&mut ctx.accounts.pool)?;
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
 
-        // 2. 借款前检查
-        check_before_borrow(&ctx, amount)?;
+        // 2. 借款前检查，返回借款后的健康因子
+        let health_factor = check_before_borrow(&ctx, amount)?;
 
-        // 3. 更新用户借款
+        // 3. 按当前借款指数换算借款份额（向上取整）
+        let pool = &ctx.accounts.pool;
+        let scaled_amount = amount_to_scaled(amount, pool.borrow_index, Rounding::Up)?;
         let user_position = &mut ctx.accounts.user_position;
-        user_position.borrowed_amount = user_position
-            .borrowed_amount
-            .checked_add(amount)
+        user_position.scaled_borrowed_amount = user_position
+            .scaled_borrowed_amount
+            .checked_add(scaled_amount)
             .ok_or(LendingError::MathOverflow)?;
+        update_position_snapshot(user_position, ctx.accounts.user.key(), pool.key(), pool)?;
+        register_position(&mut ctx.accounts.obligation, ctx.accounts.user.key(), user_position.key())?;
 
         // 4. 更新资金池
         let pool = &mut ctx.accounts.pool;
@@ -105,24 +871,31 @@ pub mod lending_pool {
             .checked_add(amount)
             .ok_or(LendingError::MathOverflow)?;
 
-        // 5. 将代币从资金池转移到用户账户
+        // 5. 由资金池 PDA 签名，将代币从资金池转移到用户账户
+        let mint = pool.mint;
+        let bump = pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
         token::transfer(
-            CpiContext::new(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 Transfer {
                     from: ctx.accounts.pool_token_account.to_account_info(),
                     to: ctx.accounts.user_token_account.to_account_info(),
                     authority: ctx.accounts.pool.to_account_info(),
                 },
+                signer_seeds,
             ),
             amount,
         )?;
 
         // 6. 记录事件
-        emit!(LendingEvent {
-            event_type: 2, // 2=借款
-            amount,
+        emit_event!(ctx, BorrowEvent {
+            pool: ctx.accounts.pool.key(),
             user: ctx.accounts.user.key(),
+            amount,
+            scaled_amount,
+            health_factor,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
@@ -130,22 +903,28 @@ pub mod lending_pool {
     }
 
     pub fn repay(ctx: Context<Repay>, amount: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_REPAY)?;
+
         // 1. 更新全局利息
-        accrue_interest(&mut ctx.accounts.pool)?;
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
 
-        // 2. 更新用户仓位
+        // 2. 还款金额不超过当前债务（含利息），并扣减借款份额
+        let pool = &ctx.accounts.pool;
         let user_position = &mut ctx.accounts.user_position;
-        user_position.borrowed_amount = user_position
-            .borrowed_amount
-            .checked_sub(amount)
+        let amount = amount.min(borrowed_amount(user_position, pool)?);
+        let scaled_amount = scaled_repay_amount(user_position, pool, amount)?;
+        user_position.scaled_borrowed_amount = user_position
+            .scaled_borrowed_amount
+            .checked_sub(scaled_amount)
             .ok_or(LendingError::MathOverflow)?;
+        update_position_snapshot(user_position, ctx.accounts.user.key(), pool.key(), pool)?;
+        release_position(&mut ctx.accounts.obligation, user_position.key(), user_position);
 
-        // 3. 更新资金池
+        // 3. 更新资金池（债务向上取整，总借款可能略小于各仓位之和）
         let pool = &mut ctx.accounts.pool;
-        pool.total_borrowed = pool
-            .total_borrowed
-            .checked_sub(amount)
-            .ok_or(LendingError::MathOverflow)?;
+        pool.total_borrowed = pool.total_borrowed.saturating_sub(amount);
 
         // 4. 转移代币到资金池
         token::transfer(
@@ -161,59 +940,176 @@ pub mod lending_pool {
         )?;
 
         // 5. 记录事件
-        emit!(LendingEvent {
-            event_type: 3, // 3=还款
-            amount,
+        emit_event!(ctx, RepayEvent {
+            pool: ctx.accounts.pool.key(),
             user: ctx.accounts.user.key(),
+            amount,
+            scaled_amount,
+            remaining_debt: borrowed_amount(&ctx.accounts.user_position, &ctx.accounts.pool)?,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
         Ok(())
     }
 
-    pub fn liquidate(
-        ctx: Context<Liquidate>,
-        repay_amount: u64, // 清算人偿还的债务金额
-        seize_amount: u64, // 清算人获取的抵押品金额
+    pub fn liquidate<'info>(
+        ctx: Context<'_, '_, '_, 'info, Liquidate<'info>>,
+        repay_amount: u64, // 清算人偿还的债务金额（借款资金池代币）
     ) -> Result<()> {
-        // 1. 更新全局利息
-        accrue_interest(&mut ctx.accounts.pool)?;
+        require_not_paused(&ctx.accounts.repay_pool, PAUSE_LIQUIDATE)?;
+        require_not_paused(&ctx.accounts.seize_pool, PAUSE_LIQUIDATE)?;
+
+        // 1. 更新两个资金池的利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.repay_pool)? {
+            emit_event!(ctx, event);
+        }
+        if let Some(event) = accrue_interest(&mut ctx.accounts.seize_pool)? {
+            emit_event!(ctx, event);
+        }
 
-        // 2. 检查健康因子
-        let health_factor = calculate_health_factor(
-            ctx.accounts.user_position.deposited_amount,
-            ctx.accounts.user_position.borrowed_amount,
-            ctx.accounts.pool.collateral_factor,
-            get_oracle_price(&ctx.accounts.feed_program,&ctx.accounts.depodit_feed,&ctx.accounts.borrow_feed)?,
+        // 2. 检查义务账户整体健康因子
+        let repay_price = get_oracle_price(&ctx.accounts.repay_pool, &ctx.accounts.repay_feed)?;
+        let seize_price = get_oracle_price(&ctx.accounts.seize_pool, &ctx.accounts.seize_feed)?;
+        let accounts = &ctx.accounts;
+        let health_factor = check_before_liquidate(
+            &accounts.obligation,
+            &[
+                (accounts.repay_position.key(), &accounts.repay_position, &accounts.repay_pool, repay_price),
+                (accounts.seize_position.key(), &accounts.seize_position, &accounts.seize_pool, seize_price),
+            ],
+            ctx.remaining_accounts,
         )?;
-        require!(health_factor < 100, LendingError::NotLiquidatable);
 
-        // 3. 更新用户仓位
-        let user_position = &mut ctx.accounts.user_position;
-        user_position.borrowed_amount = user_position
-            .borrowed_amount
-            .checked_sub(repay_amount)
-            .ok_or(LendingError::MathOverflow)?;
+        // 3. 按平仓系数与清算奖励计算实际偿还金额和没收的抵押品（只能没收已启用抵押的存款）
+        let (repay_amount, seize_amount) = calculate_liquidation_amounts(
+            repay_amount,
+            borrowed_amount(&ctx.accounts.repay_position, &ctx.accounts.repay_pool)?,
+            collateral_amount(&ctx.accounts.seize_position, &ctx.accounts.seize_pool)?,
+            &ctx.accounts.repay_pool,
+            repay_price,
+            &ctx.accounts.seize_pool,
+            seize_price,
+        )?;
 
-        user_position.deposited_amount = user_position
-            .deposited_amount
-            .checked_sub(seize_amount)
-            .ok_or(LendingError::MathOverflow)?;
+        let user = ctx.accounts.user.key();
 
-        // 4. 更新资金池
-        let pool = &mut ctx.accounts.pool;
-        pool.total_borrowed = pool
-            .total_borrowed
-            .checked_sub(repay_amount)
-            .ok_or(LendingError::MathOverflow)?;
+        // 4. 更新借款仓位与借款资金池
+        apply_liquidation_repay(&mut ctx.accounts.repay_position, &mut ctx.accounts.repay_pool, repay_amount)?;
+        let repay_pool = &ctx.accounts.repay_pool;
+        let repay_position = &mut ctx.accounts.repay_position;
+        update_position_snapshot(repay_position, user, repay_pool.key(), repay_pool)?;
+        release_position(&mut ctx.accounts.obligation, repay_position.key(), repay_position);
 
-        pool.total_supply = pool
-            .total_supply
-            .checked_sub(seize_amount)
-            .ok_or(LendingError::MathOverflow)?;
+        // 5. 更新抵押仓位与抵押资金池
+        let scaled_seize = apply_liquidation_seize(&mut ctx.accounts.seize_position, &mut ctx.accounts.seize_pool, seize_amount)?;
+        let seize_pool = &ctx.accounts.seize_pool;
+        let seize_position = &mut ctx.accounts.seize_position;
+        update_position_snapshot(seize_position, user, seize_pool.key(), seize_pool)?;
+        release_position(&mut ctx.accounts.obligation, seize_position.key(), seize_position);
 
-        // 5. 转移代币
+        // 6. 转移代币
         // 清算人偿还债务
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.liquidator_repay_token_account.to_account_info(),
+                    to: ctx.accounts.repay_pool_token_account.to_account_info(),
+                    authority: ctx.accounts.liquidator.to_account_info(),
+                },
+            ),
+            repay_amount,
+        )?;
+
+        // 清算人获取抵押品，并销毁被没收份额对应的托管凭证（由抵押资金池 PDA 签名）
+        let mint = ctx.accounts.seize_pool.mint;
+        let bump = ctx.accounts.seize_pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        token::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.seize_receipt_mint.to_account_info(),
+                    from: ctx.accounts.seize_collateral_vault.to_account_info(),
+                    authority: ctx.accounts.seize_pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            scaled_seize,
+        )?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.seize_pool_token_account.to_account_info(),
+                    to: ctx.accounts.liquidator_seize_token_account.to_account_info(),
+                    authority: ctx.accounts.seize_pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            seize_amount,
+        )?;
+
+        // 7. 记录事件
+        emit_event!(ctx, LiquidationEvent {
+            liquidator: ctx.accounts.liquidator.key(),
+            user,
+            repay_pool: ctx.accounts.repay_pool.key(),
+            seize_pool: ctx.accounts.seize_pool.key(),
+            repay_amount,
+            seize_amount,
+            health_factor,
+            repay_pool_state: pool_state_snapshot(&ctx.accounts.repay_pool)?,
+            seize_pool_state: pool_state_snapshot(&ctx.accounts.seize_pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 同一资金池清算：债务与抵押品位于同一仓位（在同一资金池中抵押并借款）
+    pub fn liquidate_same_pool<'info>(
+        ctx: Context<'_, '_, '_, 'info, LiquidateSamePool<'info>>,
+        repay_amount: u64, // 清算人偿还的债务金额
+    ) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_LIQUIDATE)?;
+
+        // 1. 更新资金池利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 检查义务账户整体健康因子
+        let price = get_oracle_price(&ctx.accounts.pool, &ctx.accounts.price_feed)?;
+        let accounts = &ctx.accounts;
+        let health_factor = check_before_liquidate(
+            &accounts.obligation,
+            &[(accounts.user_position.key(), &accounts.user_position, &accounts.pool, price)],
+            ctx.remaining_accounts,
+        )?;
+
+        // 3. 偿还与没收使用同一资金池的参数与价格
+        let (repay_amount, seize_amount) = calculate_liquidation_amounts(
+            repay_amount,
+            borrowed_amount(&ctx.accounts.user_position, &ctx.accounts.pool)?,
+            collateral_amount(&ctx.accounts.user_position, &ctx.accounts.pool)?,
+            &ctx.accounts.pool,
+            price,
+            &ctx.accounts.pool,
+            price,
+        )?;
+
+        // 4. 依次扣减同一仓位的借款与存款
+        apply_liquidation_repay(&mut ctx.accounts.user_position, &mut ctx.accounts.pool, repay_amount)?;
+        let scaled_seize = apply_liquidation_seize(&mut ctx.accounts.user_position, &mut ctx.accounts.pool, seize_amount)?;
+        let user = ctx.accounts.user.key();
+        let pool = &ctx.accounts.pool;
+        let user_position = &mut ctx.accounts.user_position;
+        update_position_snapshot(user_position, user, pool.key(), pool)?;
+        release_position(&mut ctx.accounts.obligation, user_position.key(), user_position);
+
+        // 5. 转移代币：清算人偿还债务，再由资金池 PDA 销毁被没收份额对应的托管凭证并转出抵押品
         token::transfer(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
@@ -226,24 +1122,137 @@ pub mod lending_pool {
             repay_amount,
         )?;
 
-        // 清算人获取抵押品
+        let mint = ctx.accounts.pool.mint;
+        let bump = ctx.accounts.pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        token::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.receipt_mint.to_account_info(),
+                    from: ctx.accounts.collateral_vault.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            scaled_seize,
+        )?;
         token::transfer(
-            CpiContext::new(
+            CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 Transfer {
                     from: ctx.accounts.pool_token_account.to_account_info(),
                     to: ctx.accounts.liquidator_token_account.to_account_info(),
                     authority: ctx.accounts.pool.to_account_info(),
                 },
+                signer_seeds,
             ),
             seize_amount,
         )?;
 
         // 6. 记录事件
-        emit!(LendingEvent {
-            event_type: 4, // 4=清算
-            amount: repay_amount,
+        let state = pool_state_snapshot(&ctx.accounts.pool)?;
+        emit_event!(ctx, LiquidationEvent {
+            liquidator: ctx.accounts.liquidator.key(),
+            user,
+            repay_pool: ctx.accounts.pool.key(),
+            seize_pool: ctx.accounts.pool.key(),
+            repay_amount,
+            seize_amount,
+            health_factor,
+            repay_pool_state: state,
+            seize_pool_state: state,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 闪电贷借款：同一交易的后续指令中必须包含金额一致的 flash_repay
+    pub fn flash_borrow(ctx: Context<FlashBorrow>, amount: u64) -> Result<()> {
+        require_not_paused(&ctx.accounts.pool, PAUSE_FLASH_LOAN)?;
+
+        // 1. 更新全局利息
+        if let Some(event) = accrue_interest(&mut ctx.accounts.pool)? {
+            emit_event!(ctx, event);
+        }
+
+        // 2. 同一资金池同时只能有一笔闪电贷，且不超过可用流动性
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.flash_loan_amount == 0, LendingError::FlashLoanActive);
+        require!(amount > 0, LendingError::AmountTooSmall);
+        let available_liquidity = pool.total_supply
+            .checked_sub(pool.total_borrowed)
+            .ok_or(LendingError::MathOverflow)?;
+        require!(amount <= available_liquidity, LendingError::InsufficientLiquidity);
+
+        // 3. 通过指令系统变量确认还款指令存在
+        check_flash_repay(&ctx.accounts.instructions, pool.key(), amount)?;
+        pool.flash_loan_amount = amount;
+
+        // 4. 由资金池 PDA 签名，将代币转给借款人
+        let mint = pool.mint;
+        let bump = pool.bump;
+        let signer_seeds: &[&[&[u8]]] = &[&[b"lending_pool", mint.as_ref(), &[bump]]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.pool_token_account.to_account_info(),
+                    to: ctx.accounts.destination_token_account.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        // 5. 记录事件
+        emit_event!(ctx, FlashLoanBorrowed {
+            pool: ctx.accounts.pool.key(),
+            user: ctx.accounts.user.key(),
+            amount,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    // 闪电贷还款：归还本金并支付手续费，手续费按储备金率分给协议与存款人
+    pub fn flash_repay(ctx: Context<FlashRepay>, amount: u64) -> Result<()> {
+        // 1. 必须与当前未归还的闪电贷金额一致
+        let pool = &mut ctx.accounts.pool;
+        require!(
+            pool.flash_loan_amount > 0 && pool.flash_loan_amount == amount,
+            LendingError::FlashLoanNotActive
+        );
+        pool.flash_loan_amount = 0;
+
+        // 2. 计算手续费（向上取整）并计入储备
+        let fee = flash_loan_fee(pool, amount)?;
+        distribute_fee(pool, fee)?;
+
+        // 3. 转移本金与手续费到资金池
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.pool_token_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount.checked_add(fee).ok_or(LendingError::MathOverflow)?,
+        )?;
+
+        // 4. 记录事件
+        emit_event!(ctx, FlashLoanRepaid {
+            pool: ctx.accounts.pool.key(),
             user: ctx.accounts.user.key(),
+            amount,
+            fee,
+            state: pool_state_snapshot(&ctx.accounts.pool)?,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+// 统一精度：18 位小数（1.0 = 1e18）
+pub const SCALE: usize = 18;
+pub const WAD: u64 = 1_000_000_000_000_000_000;
+// 百分比与基点到 WAD 的换算系数
+pub const PERCENT_SCALER: u64 = 10_000_000_000_000_000;
+pub const BPS_SCALER: u64 = 100_000_000_000_000;
+
+// 取整方向：协议只在对自身有利的方向取整（债务向上，份额与收益向下）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    Down,
+    Up,
+}
+
+pub trait TryAdd: Sized {
+    fn try_add(self, rhs: Self) -> Result<Self>;
+}
+
+pub trait TrySub: Sized {
+    fn try_sub(self, rhs: Self) -> Result<Self>;
+}
+
+pub trait TryMul<RHS>: Sized {
+    fn try_mul(self, rhs: RHS) -> Result<Self>;
+}
+
+pub trait TryDiv<RHS>: Sized {
+    fn try_div(self, rhs: RHS) -> Result<Self>;
+}
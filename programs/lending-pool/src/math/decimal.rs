@@ -0,0 +1,208 @@
+use std::fmt;
+
+use anchor_lang::prelude::*;
+
+use super::*;
+use crate::LendingError;
+
+// U192 由宏生成，单独放在不引入 anchor prelude 的模块中
+#[allow(clippy::assign_op_pattern)]
+#[allow(clippy::ptr_offset_with_cast)]
+#[allow(clippy::manual_range_contains)]
+#[allow(clippy::manual_div_ceil)]
+mod u192 {
+    use uint::construct_uint;
+
+    construct_uint! {
+        pub struct U192(3);
+    }
+}
+pub use u192::U192;
+
+// 18 位小数定点数，以 U192 保存，乘法中间结果不会溢出 u128
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Decimal(pub U192);
+
+impl Decimal {
+    pub fn one() -> Self {
+        Self(Self::wad())
+    }
+
+    pub fn zero() -> Self {
+        Self(U192::zero())
+    }
+
+    fn wad() -> U192 {
+        U192::from(WAD)
+    }
+
+    // 百分比（如 75 = 75%）
+    pub fn from_percent(percent: u8) -> Self {
+        Self(U192::from(percent as u64) * U192::from(PERCENT_SCALER))
+    }
+
+    // 基点（如 50 = 0.5%）
+    pub fn from_bps(bps: u64) -> Self {
+        Self(U192::from(bps) * U192::from(BPS_SCALER))
+    }
+
+    // 整数转定点数
+    pub fn from_u128(value: u128) -> Self {
+        Self(U192::from(value) * Self::wad())
+    }
+
+    // 原始 WAD 值（已含 18 位小数）
+    pub fn from_scaled_val(scaled_val: u128) -> Self {
+        Self(U192::from(scaled_val))
+    }
+
+    pub fn to_scaled_val(&self) -> Result<u128> {
+        u128::try_from(self.0).map_err(|_| error!(LendingError::MathOverflow))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    // 按指定方向取整为 u64
+    pub fn try_round_u64(&self, rounding: Rounding) -> Result<u64> {
+        let value = match rounding {
+            Rounding::Down => self.0 / Self::wad(),
+            Rounding::Up => {
+                let floor = self.0 / Self::wad();
+                if (self.0 % Self::wad()).is_zero() { floor } else { floor + U192::one() }
+            }
+        };
+        u64::try_from(value).map_err(|_| error!(LendingError::MathOverflow))
+    }
+
+    // 向下取整（存款份额、存款人收益等）
+    pub fn try_floor_u64(&self) -> Result<u64> {
+        self.try_round_u64(Rounding::Down)
+    }
+
+    // 向上取整（债务、应偿还金额等）
+    pub fn try_round_up_u64(&self) -> Result<u64> {
+        self.try_round_u64(Rounding::Up)
+    }
+
+    // 乘法，按指定方向取整
+    pub fn try_mul_round(self, rhs: Decimal, rounding: Rounding) -> Result<Self> {
+        let product = self.0.checked_mul(rhs.0).ok_or(LendingError::MathOverflow)?;
+        Ok(Self(div_round(product, Self::wad(), rounding)?))
+    }
+
+    // 除法，按指定方向取整
+    pub fn try_div_round(self, rhs: Decimal, rounding: Rounding) -> Result<Self> {
+        let numerator = self.0.checked_mul(Self::wad()).ok_or(LendingError::MathOverflow)?;
+        Ok(Self(div_round(numerator, rhs.0, rounding)?))
+    }
+}
+
+// 整数除法，按指定方向取整，除数为零时报错
+fn div_round(numerator: U192, denominator: U192, rounding: Rounding) -> Result<U192> {
+    require!(!denominator.is_zero(), LendingError::MathOverflow);
+    let quotient = numerator / denominator;
+    Ok(match rounding {
+        Rounding::Up if !(numerator % denominator).is_zero() => quotient + U192::one(),
+        _ => quotient,
+    })
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut scaled_val = self.0.to_string();
+        if scaled_val.len() <= SCALE {
+            scaled_val.insert_str(0, &"0".repeat(SCALE - scaled_val.len()));
+            scaled_val.insert_str(0, "0.");
+        } else {
+            scaled_val.insert(scaled_val.len() - SCALE, '.');
+        }
+        f.write_str(&scaled_val)
+    }
+}
+
+impl From<u64> for Decimal {
+    fn from(value: u64) -> Self {
+        Self(Self::wad() * U192::from(value))
+    }
+}
+
+impl From<Rate> for Decimal {
+    fn from(rate: Rate) -> Self {
+        Self(U192::from(rate.to_scaled_val()))
+    }
+}
+
+impl TryAdd for Decimal {
+    fn try_add(self, rhs: Self) -> Result<Self> {
+        Ok(Self(self.0.checked_add(rhs.0).ok_or(LendingError::MathOverflow)?))
+    }
+}
+
+impl TrySub for Decimal {
+    fn try_sub(self, rhs: Self) -> Result<Self> {
+        Ok(Self(self.0.checked_sub(rhs.0).ok_or(LendingError::MathOverflow)?))
+    }
+}
+
+impl TryMul<u64> for Decimal {
+    fn try_mul(self, rhs: u64) -> Result<Self> {
+        Ok(Self(self.0.checked_mul(U192::from(rhs)).ok_or(LendingError::MathOverflow)?))
+    }
+}
+
+impl TryMul<Decimal> for Decimal {
+    fn try_mul(self, rhs: Decimal) -> Result<Self> {
+        self.try_mul_round(rhs, Rounding::Down)
+    }
+}
+
+impl TryMul<Rate> for Decimal {
+    fn try_mul(self, rhs: Rate) -> Result<Self> {
+        self.try_mul(Decimal::from(rhs))
+    }
+}
+
+impl TryDiv<u64> for Decimal {
+    fn try_div(self, rhs: u64) -> Result<Self> {
+        require!(rhs != 0, LendingError::MathOverflow);
+        Ok(Self(self.0 / U192::from(rhs)))
+    }
+}
+
+impl TryDiv<Decimal> for Decimal {
+    fn try_div(self, rhs: Decimal) -> Result<Self> {
+        self.try_div_round(rhs, Rounding::Down)
+    }
+}
+
+#[test]
+fn test_decimal_rounding() {
+    // 1 / 3 = 0.333...：向下与向上取整相差 1 个最小单位
+    let third = Decimal::one().try_div(Decimal::from(3u64)).unwrap();
+    let third_up = Decimal::one().try_div_round(Decimal::from(3u64), Rounding::Up).unwrap();
+    assert_eq!(third_up.0, third.0 + U192::one());
+
+    // 10 / 3 取整
+    let value = Decimal::from(10u64).try_div(3u64).unwrap();
+    assert_eq!(value.try_floor_u64().unwrap(), 3);
+    assert_eq!(value.try_round_up_u64().unwrap(), 4);
+    // 整数不受取整方向影响
+    assert_eq!(Decimal::from(7u64).try_round_up_u64().unwrap(), 7);
+
+    // 除零与下溢返回错误而不是 panic
+    assert!(Decimal::one().try_div(Decimal::zero()).is_err());
+    assert!(Decimal::zero().try_sub(Decimal::one()).is_err());
+}
+
+#[test]
+fn test_decimal_conversions() {
+    assert_eq!(Decimal::from_percent(75).to_string(), "0.750000000000000000");
+    assert_eq!(Decimal::from_bps(50).to_string(), "0.005000000000000000");
+    assert_eq!(Decimal::from_u128(2).try_mul(Decimal::from_percent(50)).unwrap(), Decimal::one());
+    // u64 上限的数量乘以价格不会溢出，结果超出 u64 时取整报错
+    let large = Decimal::from(u64::MAX).try_mul(Decimal::from(100u64)).unwrap();
+    assert!(large.try_floor_u64().is_err());
+    assert_eq!(large.try_div(100u64).unwrap().try_floor_u64().unwrap(), u64::MAX);
+}
@@ -0,0 +1,9 @@
+// 定点数运算：Decimal（U192，用于数量与价值）与 Rate（u128，用于利率与系数）
+// 默认运算向下截断，需要向上取整时显式传入 Rounding::Up
+mod common;
+mod decimal;
+mod rate;
+
+pub use common::*;
+pub use decimal::Decimal;
+pub use rate::Rate;
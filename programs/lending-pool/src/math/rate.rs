@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+
+use super::*;
+use super::decimal::U192;
+use crate::LendingError;
+
+// 18 位小数定点数，以 u128 保存，用于利率、指数等不超过 u128 范围的系数
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Rate(pub u128);
+
+impl Rate {
+    pub fn one() -> Self {
+        Self(WAD as u128)
+    }
+
+    pub fn zero() -> Self {
+        Self(0)
+    }
+
+    // 百分比（如 10 = 10%）
+    pub fn from_percent(percent: u8) -> Self {
+        Self((percent as u128) * (PERCENT_SCALER as u128))
+    }
+
+    // 基点（如 50 = 0.5%）
+    pub fn from_bps(bps: u64) -> Self {
+        Self((bps as u128) * (BPS_SCALER as u128))
+    }
+
+    // 原始 WAD 值（已含 18 位小数）
+    pub fn from_scaled_val(scaled_val: u128) -> Self {
+        Self(scaled_val)
+    }
+
+    pub fn to_scaled_val(&self) -> u128 {
+        self.0
+    }
+
+    // 乘法，按指定方向取整（中间结果以 U192 计算）
+    pub fn try_mul_round(self, rhs: Rate, rounding: Rounding) -> Result<Self> {
+        let product = U192::from(self.0)
+            .checked_mul(U192::from(rhs.0))
+            .ok_or(LendingError::MathOverflow)?;
+        let wad = U192::from(WAD);
+        let mut quotient = product / wad;
+        if rounding == Rounding::Up && !(product % wad).is_zero() {
+            quotient += U192::one();
+        }
+        Ok(Self(u128::try_from(quotient).map_err(|_| error!(LendingError::MathOverflow))?))
+    }
+
+    // 乘方（平方求幂），每步按指定方向取整
+    pub fn try_pow(self, mut exp: u64, rounding: Rounding) -> Result<Self> {
+        let mut base = self;
+        let mut result = Rate::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.try_mul_round(base, rounding)?;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.try_mul_round(base, rounding)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl TryAdd for Rate {
+    fn try_add(self, rhs: Self) -> Result<Self> {
+        Ok(Self(self.0.checked_add(rhs.0).ok_or(LendingError::MathOverflow)?))
+    }
+}
+
+impl TrySub for Rate {
+    fn try_sub(self, rhs: Self) -> Result<Self> {
+        Ok(Self(self.0.checked_sub(rhs.0).ok_or(LendingError::MathOverflow)?))
+    }
+}
+
+impl TryMul<u64> for Rate {
+    fn try_mul(self, rhs: u64) -> Result<Self> {
+        Ok(Self(self.0.checked_mul(rhs as u128).ok_or(LendingError::MathOverflow)?))
+    }
+}
+
+impl TryMul<Rate> for Rate {
+    fn try_mul(self, rhs: Rate) -> Result<Self> {
+        self.try_mul_round(rhs, Rounding::Down)
+    }
+}
+
+impl TryDiv<u64> for Rate {
+    fn try_div(self, rhs: u64) -> Result<Self> {
+        Ok(Self(self.0.checked_div(rhs as u128).ok_or(LendingError::MathOverflow)?))
+    }
+}
+
+impl TryDiv<Rate> for Rate {
+    fn try_div(self, rhs: Rate) -> Result<Self> {
+        require!(rhs.0 != 0, LendingError::MathOverflow);
+        let numerator = U192::from(self.0)
+            .checked_mul(U192::from(WAD))
+            .ok_or(LendingError::MathOverflow)?;
+        let quotient = numerator / U192::from(rhs.0);
+        Ok(Self(u128::try_from(quotient).map_err(|_| error!(LendingError::MathOverflow))?))
+    }
+}
+
+#[test]
+fn test_rate_pow() {
+    // 1.1^2 = 1.21
+    let rate = Rate::one().try_add(Rate::from_percent(10)).unwrap();
+    assert_eq!(rate.try_pow(2, Rounding::Down).unwrap(), Rate::from_bps(12_100));
+    assert_eq!(rate.try_pow(0, Rounding::Up).unwrap(), Rate::one());
+
+    // 向上取整的结果不小于向下取整，一年逐秒复利的相对误差小于 1e-7
+    let per_second = Rate::one().try_add(Rate::from_percent(10).try_div(31_536_000).unwrap()).unwrap();
+    let down = per_second.try_pow(31_536_000, Rounding::Down).unwrap();
+    let up = per_second.try_pow(31_536_000, Rounding::Up).unwrap();
+    assert!(up >= down && up.0 - down.0 < 100_000_000_000);
+
+    assert!(Rate::one().try_div(Rate::zero()).is_err());
+}
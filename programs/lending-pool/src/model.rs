@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_lang::error_code;
-use anchor_lang::Event;
+use anchor_lang::solana_program::sysvar;
 
 use anchor_spl::token::{ Mint, TokenAccount, Token };
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::Metadata;
+
+use crate::health::HealthFactor;
 
 #[account]
 #[derive(Default, Debug)]
@@ -12,14 +15,87 @@ pub struct LendingPool {
     pub decimals: u8, // 代币精度
     pub total_supply: u64, // 总供应量（存款）
     pub total_borrowed: u64, // 总借款
-    pub liquidity_index: u128, // 流动性指数（复利计算）
-    pub borrow_index: u128, // 借款指数（复利计算）
+    pub liquidity_index: u128, // 流动性指数（18 位小数，初始为 1.0）
+    pub borrow_index: u128, // 借款指数（18 位小数，按秒复利）
     pub reserve_factor: u8, // 储备金率（如 10%）
-    pub collateral_factor: u8, // 抵押率（如 75%）
+    pub loan_to_value: u8, // 最高借款比例（如 75%，新增借款不得超过抵押价值的该比例）
+    pub liquidation_threshold: u8, // 清算阈值（如 80%，债务超过抵押价值的该比例时可被清算）
     pub last_update_time: i64, // 最后更新时间戳
-    pub base_rate: u64, // 基础利率（APR）
+    pub rate_model: InterestRateModel, // 利率模型
+    pub liquidation_bonus: u8, // 清算奖励（如 5%，清算人额外获得的抵押品比例）
+    pub close_factor: u8, // 平仓系数（如 50%，单次清算最多偿还的债务比例）
+    pub bump: u8, // 资金池 PDA bump
+    pub authority: Pubkey, // 管理员（可调整资金池参数）
+    pub pending_authority: Pubkey, // 待接受的新管理员（两步转移）
+    pub oracle_program: Pubkey, // 预言机程序地址（feed 账户的所有者）
+    pub oracle_feed: Pubkey, // 本资金池资产的价格 feed 地址
+    pub max_staleness: i64, // 报价最长有效期（秒）
+    pub oracle_type: OracleType, // 预言机类型
+    pub oracle_fixed_price: u128, // 固定价格（仅 Fixed 模式，12 位小数）
+    pub accumulated_protocol_fees: u64, // 累计未提取的协议手续费
+    pub treasury: Pubkey, // 协议手续费接收账户（代币账户）
+    pub fee_authority: Pubkey, // 手续费管理员（与管理员均可提取手续费）
+    pub receipt_mint: Pubkey, // 存款凭证 Mint（cToken，份额按流动性指数兑换）
+    pub flash_loan_fee_bps: u16, // 闪电贷手续费（基点）
+    pub flash_loan_amount: u64, // 当前交易中未归还的闪电贷金额（0 表示无）
+    pub supply_cap: u64, // 总供应上限（0 表示不限）
+    pub borrow_cap: u64, // 总借款上限（0 表示不限）
+    pub pause_guardian: Pubkey, // 暂停守护者（只能暂停，恢复需管理员）
+    pub paused_operations: u8, // 已暂停的操作（PAUSE_* 位标志）
+    pub insurance_vault: Pubkey, // 保险基金代币账户（资金池 PDA 所有）
+    pub insurance_fund_factor: u8, // 提取协议手续费时转入保险基金的比例（如 20%）
+    pub collateral_vault: Pubkey, // 仓位存款凭证托管账户（资金池 PDA 所有，与仓位存款份额一一对应）
+}
+
+// 利率模型最多支持的额外拐点数量
+pub const MAX_RATE_KINKS: usize = 3;
+
+// 可独立暂停的操作（paused_operations 位标志）
+pub const PAUSE_DEPOSIT: u8 = 1 << 0;
+pub const PAUSE_WITHDRAW: u8 = 1 << 1;
+pub const PAUSE_BORROW: u8 = 1 << 2;
+pub const PAUSE_REPAY: u8 = 1 << 3;
+pub const PAUSE_LIQUIDATE: u8 = 1 << 4;
+pub const PAUSE_FLASH_LOAN: u8 = 1 << 5;
+pub const PAUSE_ALL: u8 =
+    PAUSE_DEPOSIT | PAUSE_WITHDRAW | PAUSE_BORROW | PAUSE_REPAY | PAUSE_LIQUIDATE | PAUSE_FLASH_LOAN;
+
+// ----------------------------
+// 利率曲线拐点（最优利用率之后，利率为绝对值）
+// ----------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct RateKink {
+    pub utilization_bps: u16, // 拐点利用率（基点）
+    pub rate_bps: u32, // 拐点处年化利率（基点）
+}
+
+// ----------------------------
+// 利率模型（分段线性，参数均为基点，1% = 100）
+// ----------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct InterestRateModel {
+    pub optimal_utilization_bps: u16, // 最优利用率（如 8000 = 80%）
+    pub base_rate_bps: u32, // 利用率为 0 时的年化利率
+    pub slope1_bps: u32, // 0 至最优利用率区间的利率增量
+    pub slope2_bps: u32, // 最优利用率至 100% 区间的利率增量
+    pub extra_kinks: [RateKink; MAX_RATE_KINKS], // 最优利用率之后的额外拐点
+    pub extra_kink_count: u8, // 已启用的额外拐点数量
+    pub max_rate_bps: u32, // 年化利率上限
+}
+
+// ----------------------------
+// 预言机类型
+// ----------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum OracleType {
+    #[default]
+    Chainlink, // Chainlink store feed
+    Pyth, // Pyth pull oracle（PriceUpdateV2）
+    Switchboard, // Switchboard V2 aggregator
+    Fixed, // 固定价格（稳定币资金池）
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct InitializePool<'info> {
     // ----------------------------
@@ -29,7 +105,7 @@ pub struct InitializePool<'info> {
         init,
         payer = authority,
         space = 8 + std::mem::size_of::<LendingPool>(),
-        seeds = [b"lending_pool"],
+        seeds = [b"lending_pool", mint.key().as_ref()],
         bump
     )]
     pub pool: Account<'info, LendingPool>,
@@ -39,20 +115,78 @@ pub struct InitializePool<'info> {
     // ----------------------------
     pub mint: Account<'info, Mint>,
 
+    // ----------------------------
+    // 存款凭证账户（Mint 权限归资金池 PDA）
+    // ----------------------------
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"receipt_mint", pool.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = pool
+    )]
+    pub receipt_mint: Account<'info, Mint>,
+
+    /// CHECK: 存款凭证的 Metaplex 元数据账户，由元数据程序创建并校验
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), receipt_mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub receipt_metadata: UncheckedAccount<'info>,
+
+    // ----------------------------
+    // 保险基金账户（归资金池 PDA 所有）
+    // ----------------------------
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"insurance_vault", pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = pool
+    )]
+    pub insurance_vault: Account<'info, TokenAccount>,
+
+    // ----------------------------
+    // 仓位存款凭证托管账户（归资金池 PDA 所有）
+    // ----------------------------
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"collateral_vault", pool.key().as_ref()],
+        bump,
+        token::mint = receipt_mint,
+        token::authority = pool
+    )]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>,
+
     // ----------------------------
     // 权限账户
     // ----------------------------
     #[account(mut)]
     pub authority: Signer<'info>,
 
+    // ----------------------------
+    // 预言机相关账户
+    // ----------------------------
+    /// CHECK: 本资金池资产的价格 feed 地址，仅记录地址
+    pub price_feed: AccountInfo<'info>,
+    /// CHECK: 预言机程序（feed 账户的所有者），仅记录地址
+    pub feed_program: AccountInfo<'info>,
+
     // ----------------------------
     // 系统相关账户
     // ----------------------------
     pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     // ----------------------------
@@ -72,16 +206,19 @@ pub struct Deposit<'info> {
     #[account(
         init_if_needed,
         payer = user,
-        space = 8 + std::mem::size_of::<UserPosition>(),
-        seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
-        bump
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user
     )]
-    pub user_position: Account<'info, UserPosition>, // 用户仓位账户
+    pub user_receipt_account: Account<'info, TokenAccount>, // 用户的存款凭证账户
 
     // ----------------------------
     // 资金池相关账户
     // ----------------------------
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LendingPool>, // 资金池账户
 
     #[account(
@@ -91,6 +228,9 @@ pub struct Deposit<'info> {
     )]
     pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
 
+    #[account(mut, address = pool.receipt_mint @ LendingError::InvalidReceiptMint)]
+    pub receipt_mint: Account<'info, Mint>, // 存款凭证 Mint
+
     // ----------------------------
     // 系统相关账户
     // ----------------------------
@@ -100,8 +240,9 @@ pub struct Deposit<'info> {
     pub rent: Sysvar<'info, Rent>, // 租金系统变量
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct DepositCollateral<'info> {
+pub struct Redeem<'info> {
     // ----------------------------
     // 用户相关账户
     // ----------------------------
@@ -117,18 +258,20 @@ pub struct DepositCollateral<'info> {
     pub user_token_account: Account<'info, TokenAccount>, // 用户的代币账户
 
     #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + std::mem::size_of::<UserPosition>(),
-        seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
-        bump
+        mut,
+        token::mint = receipt_mint,
+        token::authority = user
     )]
-    pub user_position: Account<'info, UserPosition>, // 用户仓位账户
+    pub user_receipt_account: Account<'info, TokenAccount>, // 用户的存款凭证账户
 
     // ----------------------------
     // 资金池相关账户
     // ----------------------------
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LendingPool>, // 资金池账户
 
     #[account(
@@ -138,17 +281,18 @@ pub struct DepositCollateral<'info> {
     )]
     pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
 
+    #[account(mut, address = pool.receipt_mint @ LendingError::InvalidReceiptMint)]
+    pub receipt_mint: Account<'info, Mint>, // 存款凭证 Mint
+
     // ----------------------------
     // 系统相关账户
     // ----------------------------
     pub token_program: Program<'info, Token>, // SPL Token 程序
-    pub associated_token_program: Program<'info, AssociatedToken>, // 关联代币程序
-    pub system_program: Program<'info, System>, // 系统程序
-    pub rent: Sysvar<'info, Rent>, // 租金系统变量
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct Borrow<'info> {
+pub struct Withdraw<'info> {
     // ----------------------------
     // 用户相关账户
     // ----------------------------
@@ -170,10 +314,21 @@ pub struct Borrow<'info> {
     )]
     pub user_position: Account<'info, UserPosition>, // 用户仓位账户
 
+    #[account(
+        mut,
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 用户义务账户（跨资金池汇总仓位）
+
     // ----------------------------
     // 资金池相关账户
     // ----------------------------
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LendingPool>, // 资金池账户
 
     #[account(
@@ -183,21 +338,25 @@ pub struct Borrow<'info> {
     )]
     pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
 
+    #[account(mut, address = pool.receipt_mint @ LendingError::InvalidReceiptMint)]
+    pub receipt_mint: Box<Account<'info, Mint>>, // 存款凭证 Mint
+
+    #[account(mut, address = pool.collateral_vault @ LendingError::InvalidCollateralVault)]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>, // 仓位存款凭证托管账户
+
     // ----------------------------
     // 系统相关账户
     // ----------------------------
     pub token_program: Program<'info, Token>, // SPL Token 程序
     pub system_program: Program<'info, System>, // 系统程序
-    /// CHECK: 存款价格feed 地址
-    pub depodit_feed: AccountInfo<'info>,
-    /// CHECK: 借款价格feed 地址
-    pub borrow_feed: AccountInfo<'info>,
-    /// CHECK: feed 程序
-    pub feed_program: AccountInfo<'info>,
+    /// CHECK: 当前资金池的价格 feed 地址（须与资金池配置一致）
+    #[account(address = pool.oracle_feed @ LendingError::InvalidOraclePrice)]
+    pub price_feed: AccountInfo<'info>,
 }
 
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct Repay<'info> {
+pub struct DepositCollateral<'info> {
     // ----------------------------
     // 用户相关账户
     // ----------------------------
@@ -213,16 +372,31 @@ pub struct Repay<'info> {
     pub user_token_account: Account<'info, TokenAccount>, // 用户的代币账户
 
     #[account(
-        mut,
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<UserPosition>(),
         seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
         bump
     )]
     pub user_position: Account<'info, UserPosition>, // 用户仓位账户
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<Obligation>(),
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 用户义务账户（跨资金池汇总仓位）
+
     // ----------------------------
     // 资金池相关账户
     // ----------------------------
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
     pub pool: Account<'info, LendingPool>, // 资金池账户
 
     #[account(
@@ -232,35 +406,95 @@ pub struct Repay<'info> {
     )]
     pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
 
+    #[account(mut, address = pool.receipt_mint @ LendingError::InvalidReceiptMint)]
+    pub receipt_mint: Box<Account<'info, Mint>>, // 存款凭证 Mint
+
+    #[account(mut, address = pool.collateral_vault @ LendingError::InvalidCollateralVault)]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>, // 仓位存款凭证托管账户
+
     // ----------------------------
     // 系统相关账户
     // ----------------------------
     pub token_program: Program<'info, Token>, // SPL Token 程序
+    pub associated_token_program: Program<'info, AssociatedToken>, // 关联代币程序
     pub system_program: Program<'info, System>, // 系统程序
+    pub rent: Sysvar<'info, Rent>, // 租金系统变量
 }
 
+// 将持有的存款凭证转入仓位作为抵押（不新增供应）
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-pub struct Liquidate<'info> {
+pub struct DepositReceipts<'info> {
     // ----------------------------
-    // 清算人相关账户
+    // 用户相关账户
     // ----------------------------
+
     #[account(mut)]
-    pub liquidator: Signer<'info>, // 清算人签名账户
+    pub user: Signer<'info>, // 用户签名账户
 
     #[account(
         mut,
-        associated_token::mint = pool.mint,
-        associated_token::authority = liquidator
+        token::mint = pool.receipt_mint,
+        token::authority = user
+    )]
+    pub user_receipt_account: Box<Account<'info, TokenAccount>>, // 用户的存款凭证账户
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<UserPosition>(),
+        seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>, // 用户仓位账户
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<Obligation>(),
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 用户义务账户（跨资金池汇总仓位）
+
+    // ----------------------------
+    // 资金池相关账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
     )]
-    pub liquidator_token_account: Account<'info, TokenAccount>, // 清算人的代币账户
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(mut, address = pool.collateral_vault @ LendingError::InvalidCollateralVault)]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>, // 仓位存款凭证托管账户
 
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+    pub system_program: Program<'info, System>, // 系统程序
+}
+
+// 将仓位存款份额以存款凭证形式转回用户（不减少供应）
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct WithdrawReceipts<'info> {
     // ----------------------------
     // 用户相关账户
     // ----------------------------
 
-    /// CHECK: 被清算的用户
     #[account(mut)]
-    pub user: AccountInfo<'info>,
+    pub user: Signer<'info>, // 用户签名账户
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = user
+    )]
+    pub user_receipt_account: Box<Account<'info, TokenAccount>>, // 用户的存款凭证账户
 
     #[account(
         mut,
@@ -269,94 +503,1047 @@ pub struct Liquidate<'info> {
     )]
     pub user_position: Account<'info, UserPosition>, // 用户仓位账户
 
+    #[account(
+        mut,
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 用户义务账户（跨资金池汇总仓位）
+
     // ----------------------------
     // 资金池相关账户
     // ----------------------------
-    #[account(mut)]
-    pub pool: Account<'info, LendingPool>, // 资金池账户
-
     #[account(
         mut,
-        associated_token::mint = pool.mint,
-        associated_token::authority = pool
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
     )]
-    pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(address = pool.receipt_mint @ LendingError::InvalidReceiptMint)]
+    pub receipt_mint: Box<Account<'info, Mint>>, // 存款凭证 Mint
+
+    #[account(mut, address = pool.collateral_vault @ LendingError::InvalidCollateralVault)]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>, // 仓位存款凭证托管账户
 
     // ----------------------------
     // 系统相关账户
     // ----------------------------
     pub token_program: Program<'info, Token>, // SPL Token 程序
+    pub associated_token_program: Program<'info, AssociatedToken>, // 关联代币程序
     pub system_program: Program<'info, System>, // 系统程序
-    /// CHECK: 存款价格feed 地址
-    pub depodit_feed: AccountInfo<'info>,
-    /// CHECK: 借款价格feed 地址
-    pub borrow_feed: AccountInfo<'info>,
-    /// CHECK: feed 程序
-    pub feed_program: AccountInfo<'info>,
+    /// CHECK: 当前资金池的价格 feed 地址（须与资金池配置一致）
+    #[account(address = pool.oracle_feed @ LendingError::InvalidOraclePrice)]
+    pub price_feed: AccountInfo<'info>,
 }
 
-// ----------------------------
-// 用户仓位账户（记录每个用户的抵押和借款）
-// ----------------------------
-#[account]
-#[derive(Default, Debug)]
-pub struct UserPosition {
-    pub user: Pubkey, // 用户地址
-    pub pool: Pubkey, // 关联的 LendingPool
-    pub deposited_amount: u64, // 抵押品数量
-    pub borrowed_amount: u64, // 借款数量
-    pub collateral_enabled: bool, // 是否启用抵押
-    pub last_update_time: i64, // 用户仓位最后更新时间
-}
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetCollateralEnabled<'info> {
+    // ----------------------------
+    // 用户相关账户
+    // ----------------------------
 
-// ----------------------------
-// 事件记录账户（记录关键操作）
-// ----------------------------
-#[account]
-pub struct LendingEvent {
-    pub event_type: u8, // 0=存款, 1=取款, 2=借款, 3=还款, 4=清算
-    pub amount: u64,
+    pub user: Signer<'info>, // 用户签名账户
 
-    pub user: Pubkey,
-    pub timestamp: i64,
-}
+    #[account(
+        mut,
+        seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>, // 用户仓位账户
 
-impl Event for LendingEvent {
-    fn data(&self) -> Vec<u8> {
-        let mut data = Vec::with_capacity(1 + 8 + 32 + 8);
-        data.push(self.event_type);
-        data.extend_from_slice(&self.amount.to_le_bytes());
-        data.extend_from_slice(self.user.as_ref());
-        data.extend_from_slice(&self.timestamp.to_le_bytes());
-        data
-    }
-}
+    #[account(
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 用户义务账户（跨资金池汇总仓位）
 
-#[error_code]
-pub enum LendingError {
     // ----------------------------
-    // 数学计算错误
+    // 资金池相关账户
     // ----------------------------
-    #[msg("Math overflow")]
-    MathOverflow,
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
 
     // ----------------------------
-    // 借贷相关错误
+    // 预言机相关账户
     // ----------------------------
-    #[msg("Borrow limit exceeded")]
-    BorrowLimitExceeded,
+    /// CHECK: 当前资金池的价格 feed 地址（须与资金池配置一致）
+    #[account(address = pool.oracle_feed @ LendingError::InvalidOraclePrice)]
+    pub price_feed: AccountInfo<'info>,
+}
 
-    #[msg("Insufficient collateral")]
-    InsufficientCollateral,
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct Borrow<'info> {
+    // ----------------------------
+    // 用户相关账户
+    // ----------------------------
 
-    #[msg("Not liquidatable")]
-    NotLiquidatable,
+    #[account(mut)]
+    pub user: Signer<'info>, // 用户签名账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>, // 用户的代币账户
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<UserPosition>(),
+        seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>, // 用户仓位账户
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<Obligation>(),
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 用户义务账户（跨资金池汇总仓位）
 
     // ----------------------------
-    // 权限相关错误
+    // 资金池相关账户
     // ----------------------------
-    #[msg("Unauthorized")]
-    Unauthorized,
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
+
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+    pub associated_token_program: Program<'info, AssociatedToken>, // 关联代币程序
+    pub system_program: Program<'info, System>, // 系统程序
+    pub rent: Sysvar<'info, Rent>, // 租金系统变量
+    /// CHECK: 当前资金池的价格 feed 地址（须与资金池配置一致）
+    #[account(address = pool.oracle_feed @ LendingError::InvalidOraclePrice)]
+    pub price_feed: AccountInfo<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct Repay<'info> {
+    // ----------------------------
+    // 用户相关账户
+    // ----------------------------
+
+    #[account(mut)]
+    pub user: Signer<'info>, // 用户签名账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>, // 用户的代币账户
+
+    #[account(
+        mut,
+        seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>, // 用户仓位账户
+
+    #[account(
+        mut,
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 用户义务账户（跨资金池汇总仓位）
+
+    // ----------------------------
+    // 资金池相关账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
+
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+    pub system_program: Program<'info, System>, // 系统程序
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    // ----------------------------
+    // 清算人相关账户
+    // ----------------------------
+    #[account(mut)]
+    pub liquidator: Signer<'info>, // 清算人签名账户
+
+    #[account(
+        mut,
+        associated_token::mint = repay_pool.mint,
+        associated_token::authority = liquidator
+    )]
+    pub liquidator_repay_token_account: Account<'info, TokenAccount>, // 清算人偿还债务使用的代币账户
+
+    #[account(
+        mut,
+        associated_token::mint = seize_pool.mint,
+        associated_token::authority = liquidator
+    )]
+    pub liquidator_seize_token_account: Account<'info, TokenAccount>, // 清算人接收抵押品的代币账户
+
+    // ----------------------------
+    // 用户相关账户
+    // ----------------------------
+
+    /// CHECK: 被清算的用户
+    #[account(mut)]
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 被清算用户的义务账户（清空的仓位将被移除）
+
+    #[account(
+        mut,
+        seeds = [b"user_position", user.key().as_ref(), repay_pool.key().as_ref()],
+        bump
+    )]
+    pub repay_position: Account<'info, UserPosition>, // 被偿还债务所在的用户仓位
+
+    #[account(
+        mut,
+        seeds = [b"user_position", user.key().as_ref(), seize_pool.key().as_ref()],
+        bump
+    )]
+    pub seize_position: Account<'info, UserPosition>, // 被没收抵押品所在的用户仓位
+
+    // ----------------------------
+    // 资金池相关账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", repay_pool.mint.as_ref()],
+        bump = repay_pool.bump
+    )]
+    pub repay_pool: Account<'info, LendingPool>, // 借款资金池
+
+    #[account(
+        mut,
+        associated_token::mint = repay_pool.mint,
+        associated_token::authority = repay_pool
+    )]
+    pub repay_pool_token_account: Account<'info, TokenAccount>, // 借款资金池的代币账户
+
+    #[account(
+        mut,
+        seeds = [b"lending_pool", seize_pool.mint.as_ref()],
+        bump = seize_pool.bump,
+        constraint = seize_pool.key() != repay_pool.key() @ LendingError::SamePoolLiquidation
+    )]
+    pub seize_pool: Account<'info, LendingPool>, // 抵押资金池
+
+    #[account(
+        mut,
+        associated_token::mint = seize_pool.mint,
+        associated_token::authority = seize_pool
+    )]
+    pub seize_pool_token_account: Account<'info, TokenAccount>, // 抵押资金池的代币账户
+
+    #[account(mut, address = seize_pool.receipt_mint @ LendingError::InvalidReceiptMint)]
+    pub seize_receipt_mint: Box<Account<'info, Mint>>, // 抵押资金池的存款凭证 Mint
+
+    #[account(mut, address = seize_pool.collateral_vault @ LendingError::InvalidCollateralVault)]
+    pub seize_collateral_vault: Box<Account<'info, TokenAccount>>, // 抵押资金池的仓位存款凭证托管账户
+
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+    pub system_program: Program<'info, System>, // 系统程序
+    /// CHECK: 借款资金池的价格 feed 地址（须与资金池配置一致）
+    #[account(address = repay_pool.oracle_feed @ LendingError::InvalidOraclePrice)]
+    pub repay_feed: AccountInfo<'info>,
+    /// CHECK: 抵押资金池的价格 feed 地址（须与资金池配置一致）
+    #[account(address = seize_pool.oracle_feed @ LendingError::InvalidOraclePrice)]
+    pub seize_feed: AccountInfo<'info>,
+}
+
+// 同一资金池中的借款以该资金池的存款作抵押时使用（偿还与没收为同一仓位）
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct LiquidateSamePool<'info> {
+    // ----------------------------
+    // 清算人相关账户
+    // ----------------------------
+    #[account(mut)]
+    pub liquidator: Signer<'info>, // 清算人签名账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = liquidator
+    )]
+    pub liquidator_token_account: Account<'info, TokenAccount>, // 清算人偿还债务并接收抵押品的代币账户
+
+    // ----------------------------
+    // 用户相关账户
+    // ----------------------------
+
+    /// CHECK: 被清算的用户
+    #[account(mut)]
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 被清算用户的义务账户（清空的仓位将被移除）
+
+    #[account(
+        mut,
+        seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>, // 同时持有债务与抵押品的用户仓位
+
+    // ----------------------------
+    // 资金池相关账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
+
+    #[account(mut, address = pool.receipt_mint @ LendingError::InvalidReceiptMint)]
+    pub receipt_mint: Box<Account<'info, Mint>>, // 存款凭证 Mint
+
+    #[account(mut, address = pool.collateral_vault @ LendingError::InvalidCollateralVault)]
+    pub collateral_vault: Box<Account<'info, TokenAccount>>, // 仓位存款凭证托管账户
+
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+    pub system_program: Program<'info, System>, // 系统程序
+    /// CHECK: 资金池的价格 feed 地址（须与资金池配置一致）
+    #[account(address = pool.oracle_feed @ LendingError::InvalidOraclePrice)]
+    pub price_feed: AccountInfo<'info>,
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    // ----------------------------
+    // 资金池账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ LendingError::Unauthorized
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    // ----------------------------
+    // 权限账户
+    // ----------------------------
+    pub authority: Signer<'info>, // 当前管理员
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct FlashBorrow<'info> {
+    // ----------------------------
+    // 用户相关账户
+    // ----------------------------
+    pub user: Signer<'info>, // 用户签名账户
+
+    #[account(mut, token::mint = pool.mint)]
+    pub destination_token_account: Account<'info, TokenAccount>, // 接收借款的代币账户
+
+    // ----------------------------
+    // 资金池相关账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
+
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    /// CHECK: 指令系统变量，用于检查同一交易中的还款指令
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct FlashRepay<'info> {
+    // ----------------------------
+    // 用户相关账户
+    // ----------------------------
+    pub user: Signer<'info>, // 用户签名账户
+
+    #[account(
+        mut,
+        token::mint = pool.mint,
+        token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>, // 还款来源代币账户
+
+    // ----------------------------
+    // 资金池相关账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
+
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CollectProtocolFees<'info> {
+    // ----------------------------
+    // 资金池相关账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        constraint = authority.key() == pool.authority ||
+            authority.key() == pool.fee_authority @ LendingError::Unauthorized
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
+
+    #[account(
+        mut,
+        address = pool.treasury @ LendingError::InvalidTreasury,
+        token::mint = pool.mint
+    )]
+    pub treasury: Account<'info, TokenAccount>, // 协议手续费接收账户
+
+    #[account(
+        mut,
+        address = pool.insurance_vault @ LendingError::InvalidInsuranceVault
+    )]
+    pub insurance_vault: Account<'info, TokenAccount>, // 保险基金账户
+
+    // ----------------------------
+    // 权限账户
+    // ----------------------------
+    pub authority: Signer<'info>, // 管理员或手续费管理员
+
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    // ----------------------------
+    // 资金池账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ LendingError::Unauthorized
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    // ----------------------------
+    // 权限账户
+    // ----------------------------
+    pub authority: Signer<'info>, // 当前管理员
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    // ----------------------------
+    // 资金池账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        constraint = pool.pending_authority == pending_authority.key() @ LendingError::Unauthorized
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    // ----------------------------
+    // 权限账户
+    // ----------------------------
+    pub pending_authority: Signer<'info>, // 被提名的新管理员
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SettleBadDebt<'info> {
+    // ----------------------------
+    // 用户相关账户
+    // ----------------------------
+
+    /// CHECK: 坏账所属的用户
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_position", user.key().as_ref(), pool.key().as_ref()],
+        bump
+    )]
+    pub user_position: Account<'info, UserPosition>, // 坏账所在的用户仓位
+
+    #[account(
+        mut,
+        seeds = [b"obligation", user.key().as_ref()],
+        bump
+    )]
+    pub obligation: Account<'info, Obligation>, // 用户义务账户
+
+    // ----------------------------
+    // 资金池相关账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        has_one = authority @ LendingError::Unauthorized
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    #[account(
+        mut,
+        associated_token::mint = pool.mint,
+        associated_token::authority = pool
+    )]
+    pub pool_token_account: Account<'info, TokenAccount>, // 资金池的代币账户
+
+    #[account(
+        mut,
+        address = pool.insurance_vault @ LendingError::InvalidInsuranceVault
+    )]
+    pub insurance_vault: Account<'info, TokenAccount>, // 保险基金账户
+
+    // ----------------------------
+    // 权限账户
+    // ----------------------------
+    pub authority: Signer<'info>, // 管理员
+
+    // ----------------------------
+    // 系统相关账户
+    // ----------------------------
+    pub token_program: Program<'info, Token>, // SPL Token 程序
+}
+
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetPausedOperations<'info> {
+    // ----------------------------
+    // 资金池账户
+    // ----------------------------
+    #[account(
+        mut,
+        seeds = [b"lending_pool", pool.mint.as_ref()],
+        bump = pool.bump,
+        constraint = authority.key() == pool.authority ||
+            authority.key() == pool.pause_guardian @ LendingError::Unauthorized
+    )]
+    pub pool: Account<'info, LendingPool>, // 资金池账户
+
+    // ----------------------------
+    // 权限账户
+    // ----------------------------
+    pub authority: Signer<'info>, // 管理员或暂停守护者
+}
+
+// ----------------------------
+// 资金池初始化参数
+// ----------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct InitPoolParams {
+    pub reserve_factor: u8, // 储备金率
+    pub loan_to_value: u8, // 最高借款比例
+    pub liquidation_threshold: u8, // 清算阈值
+    pub rate_model: InterestRateModel, // 利率模型
+    pub liquidation_bonus: u8, // 清算奖励
+    pub close_factor: u8, // 平仓系数
+    pub max_staleness: i64, // 报价最长有效期（秒）
+    pub oracle_type: OracleType, // 预言机类型
+    pub oracle_fixed_price: u128, // 固定价格（仅 Fixed 模式）
+    pub treasury: Pubkey, // 协议手续费接收账户
+    pub fee_authority: Pubkey, // 手续费管理员
+    pub flash_loan_fee_bps: u16, // 闪电贷手续费（基点）
+    pub supply_cap: u64, // 总供应上限（0 表示不限）
+    pub borrow_cap: u64, // 总借款上限（0 表示不限）
+    pub pause_guardian: Pubkey, // 暂停守护者
+    pub insurance_fund_factor: u8, // 协议手续费转入保险基金的比例
+    pub receipt_name: String, // 存款凭证名称（Metaplex 元数据）
+    pub receipt_symbol: String, // 存款凭证符号
+    pub receipt_uri: String, // 存款凭证元数据 URI
+}
+
+// ----------------------------
+// 资金池参数更新（未设置的字段保持不变）
+// ----------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct PoolConfigParams {
+    pub reserve_factor: Option<u8>, // 储备金率
+    pub loan_to_value: Option<u8>, // 最高借款比例
+    pub liquidation_threshold: Option<u8>, // 清算阈值
+    pub liquidation_bonus: Option<u8>, // 清算奖励
+    pub close_factor: Option<u8>, // 平仓系数
+    pub oracle_program: Option<Pubkey>, // 预言机程序地址
+    pub oracle_feed: Option<Pubkey>, // 价格 feed 地址
+    pub max_staleness: Option<i64>, // 报价最长有效期（秒）
+    pub oracle_type: Option<OracleType>, // 预言机类型
+    pub oracle_fixed_price: Option<u128>, // 固定价格（仅 Fixed 模式）
+    pub treasury: Option<Pubkey>, // 协议手续费接收账户
+    pub fee_authority: Option<Pubkey>, // 手续费管理员
+    pub flash_loan_fee_bps: Option<u16>, // 闪电贷手续费（基点）
+    pub supply_cap: Option<u64>, // 总供应上限（0 表示不限）
+    pub borrow_cap: Option<u64>, // 总借款上限（0 表示不限）
+    pub pause_guardian: Option<Pubkey>, // 暂停守护者
+    pub insurance_fund_factor: Option<u8>, // 协议手续费转入保险基金的比例
+}
+
+// ----------------------------
+// 用户仓位账户（记录每个用户的抵押和借款）
+// ----------------------------
+#[account]
+#[derive(Default, Debug)]
+pub struct UserPosition {
+    pub user: Pubkey, // 用户地址
+    pub pool: Pubkey, // 关联的 LendingPool
+    pub scaled_deposited_amount: u64, // 存款份额（存款数量 / 流动性指数）
+    pub scaled_borrowed_amount: u64, // 借款份额（借款数量 / 借款指数）
+    pub liquidity_index_snapshot: u128, // 仓位最后更新时的流动性指数
+    pub borrow_index_snapshot: u128, // 仓位最后更新时的借款指数
+    pub collateral_enabled: bool, // 是否启用抵押
+    pub last_update_time: i64, // 用户仓位最后更新时间
+}
+
+// 义务账户最多可关联的仓位数量
+pub const MAX_OBLIGATION_POSITIONS: usize = 8;
+
+// ----------------------------
+// 义务账户（汇总用户在各资金池中的仓位，用于跨资产健康检查）
+// ----------------------------
+#[account]
+#[derive(Default, Debug)]
+pub struct Obligation {
+    pub owner: Pubkey, // 用户地址
+    pub positions: [Pubkey; MAX_OBLIGATION_POSITIONS], // 已关联的 UserPosition 地址
+    pub position_count: u8, // 已关联仓位数量
+}
+
+// ----------------------------
+// 业务事件（均附带操作完成后的资金池状态，索引器可仅凭日志重建资金池）
+// ----------------------------
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStateSnapshot {
+    pub total_supply: u64,
+    pub total_borrowed: u64,
+    pub liquidity_index: u128, // 18 位小数
+    pub borrow_index: u128, // 18 位小数
+    pub utilization_rate: u128, // 18 位小数
+    pub borrow_rate: u128, // 年化借款利率，18 位小数
+    pub supply_rate: u128, // 年化存款利率，18 位小数
+    pub accumulated_protocol_fees: u64,
+}
+
+#[event]
+pub struct DepositEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub scaled_amount: u64, // 新增的存款份额（存款凭证数量或仓位份额）
+    pub to_position: bool, // 是否存入仓位（否则为铸造存款凭证）
+    pub collateral: bool, // 存入后仓位是否启用抵押（铸造存款凭证时为 false）
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+// 存款凭证转入仓位作为抵押（总供应不变）
+#[event]
+pub struct CollateralReceiptsDeposited {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64, // 转入的凭证数量（即新增的仓位存款份额）
+    pub amount: u64, // 凭证当前可兑换的代币数量
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+// 仓位存款份额以存款凭证形式转回用户（总供应不变）
+#[event]
+pub struct CollateralReceiptsWithdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub shares: u64, // 转出的凭证数量（即扣减的仓位存款份额）
+    pub amount: u64, // 凭证当前可兑换的代币数量
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollateralToggledEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub enabled: bool,
+    pub health_factor: Option<HealthFactor>, // 停用抵押后义务账户的健康因子（按最高借款比例），启用或本就未启用时为 None
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub scaled_amount: u64, // 扣减的存款份额（销毁的存款凭证数量或仓位份额）
+    pub from_position: bool, // 是否从仓位中取出（否则为赎回存款凭证）
+    pub collateral: bool, // 取出时仓位是否启用抵押（赎回存款凭证时为 false）
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BorrowEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub scaled_amount: u64, // 新增的借款份额
+    pub health_factor: HealthFactor, // 借款后义务账户的健康因子（按最高借款比例）
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RepayEvent {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub scaled_amount: u64, // 扣减的借款份额
+    pub remaining_debt: u64, // 还款后的剩余债务
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationEvent {
+    pub liquidator: Pubkey,
+    pub user: Pubkey,
+    pub repay_pool: Pubkey,
+    pub seize_pool: Pubkey,
+    pub repay_amount: u64,
+    pub seize_amount: u64,
+    pub health_factor: HealthFactor, // 清算前义务账户的健康因子（按清算阈值）
+    pub repay_pool_state: PoolStateSnapshot,
+    pub seize_pool_state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InterestAccruedEvent {
+    pub pool: Pubkey,
+    pub elapsed: i64, // 距上次结算经过的秒数
+    pub interest: u64, // 新增借款利息
+    pub supply_interest: u64, // 其中存款人收益
+    pub protocol_fee: u64, // 其中协议手续费
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+// ----------------------------
+// 管理事件
+// ----------------------------
+#[event]
+pub struct PoolConfigUpdated {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub reserve_factor: u8,
+    pub loan_to_value: u8,
+    pub liquidation_threshold: u8,
+    pub liquidation_bonus: u8,
+    pub close_factor: u8,
+    pub oracle_program: Pubkey,
+    pub oracle_feed: Pubkey,
+    pub max_staleness: i64,
+    pub oracle_type: OracleType,
+    pub oracle_fixed_price: u128,
+    pub treasury: Pubkey,
+    pub fee_authority: Pubkey,
+    pub flash_loan_fee_bps: u16,
+    pub supply_cap: u64,
+    pub borrow_cap: u64,
+    pub pause_guardian: Pubkey,
+    pub insurance_fund_factor: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RateModelUpdated {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub rate_model: InterestRateModel,
+    pub borrow_rate: u128, // 按新模型计算的当前借款利率（WAD）
+    pub supply_rate: u128, // 按新模型计算的当前存款利率（WAD）
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub authority: Pubkey,
+    pub receipt_mint: Pubkey,
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlashLoanBorrowed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FlashLoanRepaid {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub state: PoolStateSnapshot,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolFeesCollected {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub insurance_amount: u64, // 其中转入保险基金的部分
+    pub remaining_fees: u64, // 因资金池余额不足而暂未提取的手续费
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BadDebtSettled {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub bad_debt: u64, // 核销的债务总额
+    pub insurance_covered: u64, // 由保险基金弥补的部分
+    pub socialized_loss: u64, // 由存款人分摊的部分
+    pub state: PoolStateSnapshot, // 分摊后的资金池状态
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PausedOperationsUpdated {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub previous_paused_operations: u8,
+    pub paused_operations: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityAccepted {
+    pub pool: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum LendingError {
+    // ----------------------------
+    // 数学计算错误
+    // ----------------------------
+    #[msg("Math overflow")]
+    MathOverflow,
+
+    // ----------------------------
+    // 借贷相关错误
+    // ----------------------------
+    #[msg("Borrow limit exceeded")]
+    BorrowLimitExceeded,
+
+    #[msg("Insufficient collateral")]
+    InsufficientCollateral,
+
+    #[msg("Not liquidatable")]
+    NotLiquidatable,
+
+    #[msg("Insufficient liquidity")]
+    InsufficientLiquidity,
+
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+
+    #[msg("Collateral not enabled")]
+    CollateralNotEnabled,
+
+    #[msg("Repay and seize pools must differ, use liquidate_same_pool")]
+    SamePoolLiquidation,
+
+    #[msg("Amount too small")]
+    AmountTooSmall,
+
+    #[msg("Invalid receipt mint")]
+    InvalidReceiptMint,
+
+    #[msg("Supply cap exceeded")]
+    SupplyCapExceeded,
+
+    #[msg("Borrow cap exceeded")]
+    BorrowCapExceeded,
+
+    // ----------------------------
+    // 义务账户相关错误
+    // ----------------------------
+    #[msg("Obligation position limit reached")]
+    ObligationFull,
+
+    #[msg("Invalid obligation accounts")]
+    InvalidObligationAccounts,
+
+    // ----------------------------
+    // 权限相关错误
+    // ----------------------------
+    #[msg("Unauthorized")]
+    Unauthorized,
+
+    #[msg("Invalid pool config")]
+    InvalidPoolConfig,
+
+    #[msg("Operation is paused")]
+    OperationPaused,
+
+    // ----------------------------
+    // 手续费相关错误
+    // ----------------------------
+    #[msg("Invalid treasury account")]
+    InvalidTreasury,
+
+    #[msg("No protocol fees to collect")]
+    NoProtocolFees,
+
+    // ----------------------------
+    // 坏账相关错误
+    // ----------------------------
+    #[msg("Invalid insurance vault")]
+    InvalidInsuranceVault,
+    #[msg("Invalid collateral vault")]
+    InvalidCollateralVault,
+
+    #[msg("Obligation still has deposits")]
+    ObligationHasDeposits,
+
+    #[msg("No bad debt to settle")]
+    NoBadDebt,
+
+    // ----------------------------
+    // 闪电贷相关错误
+    // ----------------------------
+    #[msg("Flash loan already active")]
+    FlashLoanActive,
+
+    #[msg("No matching active flash loan")]
+    FlashLoanNotActive,
+
+    #[msg("Missing flash repay instruction")]
+    FlashRepayMissing,
+
+    #[msg("Flash borrow must be a top-level instruction")]
+    FlashLoanCpiNotAllowed,
 
     // ----------------------------
     // 账户相关错误
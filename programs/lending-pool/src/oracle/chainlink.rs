@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use super::{ read_bytes, PriceSource, RawPrice };
+use crate::LendingError;
+
+// Chainlink store 程序中 Transmissions 账户的布局
+// [0..8] 账户标识 | [8..200] 头部 | [200..] 最新报价环形缓冲区（每条 48 字节）
+const DISCRIMINATOR: [u8; 8] = [96, 179, 69, 66, 128, 129, 73, 117];
+const DECIMALS_OFFSET: usize = 138;
+const LATEST_ROUND_ID_OFFSET: usize = 143;
+const LIVE_LENGTH_OFFSET: usize = 148;
+const LIVE_CURSOR_OFFSET: usize = 152;
+const TRANSMISSIONS_OFFSET: usize = 8 + 192;
+const TRANSMISSION_SIZE: usize = 48;
+
+// 单条报价：slot(u64) | timestamp(u32) | 填充(u32) | answer(i128) | 填充(u64 * 2)
+const TIMESTAMP_OFFSET: usize = 8;
+const ANSWER_OFFSET: usize = 16;
+
+pub struct ChainlinkFeed<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ChainlinkFeed<'a> {
+    pub fn try_from_data(data: &'a [u8]) -> Result<Self> {
+        require!(
+            data.len() >= TRANSMISSIONS_OFFSET && data[..8] == DISCRIMINATOR,
+            LendingError::InvalidOraclePrice
+        );
+        Ok(Self { data })
+    }
+}
+
+impl PriceSource for ChainlinkFeed<'_> {
+    fn latest_price(&self, _now: i64) -> Result<RawPrice> {
+        let latest_round_id = u32::from_le_bytes(read_bytes(self.data, LATEST_ROUND_ID_OFFSET)?);
+        let live_length = u32::from_le_bytes(read_bytes(self.data, LIVE_LENGTH_OFFSET)?);
+        let live_cursor = u32::from_le_bytes(read_bytes(self.data, LIVE_CURSOR_OFFSET)?);
+        require!(latest_round_id > 0 && live_length > 0, LendingError::InvalidOraclePrice);
+
+        // live_cursor 指向下一条写入位置，最新报价位于其前一条
+        let index = ((live_cursor as u64 + live_length as u64 - 1) % live_length as u64) as usize;
+        let offset = TRANSMISSIONS_OFFSET + index * TRANSMISSION_SIZE;
+
+        Ok(RawPrice {
+            answer: i128::from_le_bytes(read_bytes(self.data, offset + ANSWER_OFFSET)?),
+            decimals: self.data[DECIMALS_OFFSET],
+            timestamp: u32::from_le_bytes(read_bytes(self.data, offset + TIMESTAMP_OFFSET)?) as i64,
+        })
+    }
+}
+
+#[cfg(test)]
+pub fn mock_feed_data(decimals: u8, answers: &[(u32, i128)]) -> Vec<u8> {
+    let mut data = vec![0u8; TRANSMISSIONS_OFFSET + answers.len() * TRANSMISSION_SIZE];
+    data[..8].copy_from_slice(&DISCRIMINATOR);
+    data[DECIMALS_OFFSET] = decimals;
+    data[LATEST_ROUND_ID_OFFSET..LATEST_ROUND_ID_OFFSET + 4].copy_from_slice(&(answers.len() as u32).to_le_bytes());
+    data[LIVE_LENGTH_OFFSET..LIVE_LENGTH_OFFSET + 4].copy_from_slice(&(answers.len() as u32).to_le_bytes());
+    for (index, (timestamp, answer)) in answers.iter().enumerate() {
+        let offset = TRANSMISSIONS_OFFSET + index * TRANSMISSION_SIZE;
+        data[offset + TIMESTAMP_OFFSET..offset + TIMESTAMP_OFFSET + 4].copy_from_slice(&timestamp.to_le_bytes());
+        data[offset + ANSWER_OFFSET..offset + ANSWER_OFFSET + 16].copy_from_slice(&answer.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn test_chainlink_latest_price() {
+    let mut data = mock_feed_data(8, &[(100, 14_000_000_000), (160, 15_000_000_000)]);
+
+    // 游标回绕到 0 时，最新报价是缓冲区最后一条
+    let price = ChainlinkFeed::try_from_data(&data).unwrap().latest_price(0).unwrap();
+    assert_eq!(price, RawPrice { answer: 15_000_000_000, decimals: 8, timestamp: 160 });
+
+    // 游标为 1 时，最新报价是第一条
+    data[LIVE_CURSOR_OFFSET..LIVE_CURSOR_OFFSET + 4].copy_from_slice(&1u32.to_le_bytes());
+    let price = ChainlinkFeed::try_from_data(&data).unwrap().latest_price(0).unwrap();
+    assert_eq!(price.answer, 14_000_000_000);
+
+    // 账户标识不符时拒绝
+    data[0] = 0;
+    assert!(ChainlinkFeed::try_from_data(&data).is_err());
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use super::{ PriceSource, RawPrice };
+use crate::price::PRICE_DECIMALS;
+
+// 固定价格（用于稳定币资金池），以统一价格精度保存在资金池中，永不过期
+pub struct FixedPrice {
+    price: u128,
+}
+
+impl FixedPrice {
+    pub fn new(price: u128) -> Self {
+        Self { price }
+    }
+}
+
+impl PriceSource for FixedPrice {
+    fn latest_price(&self, now: i64) -> Result<RawPrice> {
+        Ok(RawPrice {
+            answer: self.price as i128,
+            decimals: PRICE_DECIMALS,
+            timestamp: now,
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{ LendingError, OracleType };
+
+mod chainlink;
+mod fixed;
+mod pyth;
+mod switchboard;
+
+pub use chainlink::ChainlinkFeed;
+pub use fixed::FixedPrice;
+pub use pyth::PythPriceUpdate;
+#[cfg(test)]
+pub use pyth::{ mock_price_update_data, VerificationLevel };
+pub use switchboard::SwitchboardAggregator;
+
+// 预言机原始报价：价格 = answer / 10^decimals，发布时间为 timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawPrice {
+    pub answer: i128,
+    pub decimals: u8,
+    pub timestamp: i64,
+}
+
+// 价格来源：每种预言机后端直接从账户数据中解析最新报价，不依赖 CPI
+pub trait PriceSource {
+    fn latest_price(&self, now: i64) -> Result<RawPrice>;
+}
+
+// 按预言机类型解析 feed 账户数据（固定价格模式不读取账户）
+pub fn read_raw_price(
+    oracle_type: OracleType,
+    data: &[u8],
+    fixed_price: u128,
+    now: i64
+) -> Result<RawPrice> {
+    match oracle_type {
+        OracleType::Chainlink => ChainlinkFeed::try_from_data(data)?.latest_price(now),
+        OracleType::Pyth => PythPriceUpdate::try_from_data(data)?.latest_price(now),
+        OracleType::Switchboard => SwitchboardAggregator::try_from_data(data)?.latest_price(now),
+        OracleType::Fixed => FixedPrice::new(fixed_price).latest_price(now),
+    }
+}
+
+// 从账户数据的 offset 处读取定长字节
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    let bytes = data.get(offset..offset + N).ok_or(LendingError::InvalidOraclePrice)?;
+    Ok(bytes.try_into().unwrap())
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+
+use super::{ PriceSource, RawPrice };
+use crate::LendingError;
+
+// Pyth pull oracle 的 PriceUpdateV2 账户标识
+const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VerificationLevel {
+    Partial {
+        num_signatures: u8,
+    },
+    Full,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PriceFeedMessage {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PythPriceUpdate {
+    pub write_authority: Pubkey,
+    pub verification_level: VerificationLevel,
+    pub price_message: PriceFeedMessage,
+    pub posted_slot: u64,
+}
+
+impl PythPriceUpdate {
+    pub fn try_from_data(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() >= 8 && data[..8] == DISCRIMINATOR,
+            LendingError::InvalidOraclePrice
+        );
+        Self::deserialize(&mut &data[8..]).map_err(|_| error!(LendingError::InvalidOraclePrice))
+    }
+}
+
+impl PriceSource for PythPriceUpdate {
+    fn latest_price(&self, _now: i64) -> Result<RawPrice> {
+        // 只接受经过完整 Wormhole 签名验证的报价
+        require!(
+            self.verification_level == VerificationLevel::Full,
+            LendingError::InvalidOraclePrice
+        );
+
+        // Pyth 以负指数表示小数位，例如 -8 表示 8 位小数
+        let exponent = self.price_message.exponent;
+        require!((-38..=0).contains(&exponent), LendingError::InvalidOraclePrice);
+
+        Ok(RawPrice {
+            answer: self.price_message.price as i128,
+            decimals: (-exponent) as u8,
+            timestamp: self.price_message.publish_time,
+        })
+    }
+}
+
+#[cfg(test)]
+pub fn mock_price_update_data(price: i64, exponent: i32, publish_time: i64, verification_level: VerificationLevel) -> Vec<u8> {
+    let update = PythPriceUpdate {
+        write_authority: Pubkey::default(),
+        verification_level,
+        price_message: PriceFeedMessage {
+            feed_id: [0; 32],
+            price,
+            conf: 0,
+            exponent,
+            publish_time,
+            prev_publish_time: publish_time,
+            ema_price: price,
+            ema_conf: 0,
+        },
+        posted_slot: 0,
+    };
+    let mut data = DISCRIMINATOR.to_vec();
+    update.serialize(&mut data).unwrap();
+    data
+}
+
+#[test]
+fn test_pyth_latest_price() {
+    let data = mock_price_update_data(15_000_000_000, -8, 160, VerificationLevel::Full);
+    let price = PythPriceUpdate::try_from_data(&data).unwrap().latest_price(0).unwrap();
+    assert_eq!(price, RawPrice { answer: 15_000_000_000, decimals: 8, timestamp: 160 });
+
+    // 部分验证的报价被拒绝
+    let data = mock_price_update_data(
+        15_000_000_000,
+        -8,
+        160,
+        VerificationLevel::Partial { num_signatures: 3 }
+    );
+    assert!(PythPriceUpdate::try_from_data(&data).unwrap().latest_price(0).is_err());
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use super::{ read_bytes, PriceSource, RawPrice };
+use crate::LendingError;
+
+// Switchboard V2 AggregatorAccountData 账户布局（紧凑排列）
+const DISCRIMINATOR: [u8; 8] = [217, 230, 65, 101, 201, 162, 27, 125];
+const MIN_ORACLE_RESULTS_OFFSET: usize = 236;
+// latest_confirmed_round 起始于 341
+const NUM_SUCCESS_OFFSET: usize = 341;
+const ROUND_OPEN_TIMESTAMP_OFFSET: usize = 358;
+const RESULT_MANTISSA_OFFSET: usize = 366;
+const RESULT_SCALE_OFFSET: usize = 382;
+const MIN_DATA_LEN: usize = RESULT_SCALE_OFFSET + 4;
+
+pub struct SwitchboardAggregator<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> SwitchboardAggregator<'a> {
+    pub fn try_from_data(data: &'a [u8]) -> Result<Self> {
+        require!(
+            data.len() >= MIN_DATA_LEN && data[..8] == DISCRIMINATOR,
+            LendingError::InvalidOraclePrice
+        );
+        Ok(Self { data })
+    }
+}
+
+impl PriceSource for SwitchboardAggregator<'_> {
+    fn latest_price(&self, _now: i64) -> Result<RawPrice> {
+        // 最新确认轮次需达到最少预言机响应数
+        let min_oracle_results = u32::from_le_bytes(read_bytes(self.data, MIN_ORACLE_RESULTS_OFFSET)?);
+        let num_success = u32::from_le_bytes(read_bytes(self.data, NUM_SUCCESS_OFFSET)?);
+        require!(
+            num_success > 0 && num_success >= min_oracle_results,
+            LendingError::InvalidOraclePrice
+        );
+
+        // 结果为 SwitchboardDecimal：mantissa / 10^scale
+        let scale = u32::from_le_bytes(read_bytes(self.data, RESULT_SCALE_OFFSET)?);
+        require!(scale <= 38, LendingError::InvalidOraclePrice);
+
+        Ok(RawPrice {
+            answer: i128::from_le_bytes(read_bytes(self.data, RESULT_MANTISSA_OFFSET)?),
+            decimals: scale as u8,
+            timestamp: i64::from_le_bytes(read_bytes(self.data, ROUND_OPEN_TIMESTAMP_OFFSET)?),
+        })
+    }
+}
+
+#[cfg(test)]
+pub fn mock_aggregator_data(mantissa: i128, scale: u32, timestamp: i64, num_success: u32) -> Vec<u8> {
+    let mut data = vec![0u8; MIN_DATA_LEN];
+    data[..8].copy_from_slice(&DISCRIMINATOR);
+    data[MIN_ORACLE_RESULTS_OFFSET..MIN_ORACLE_RESULTS_OFFSET + 4].copy_from_slice(&1u32.to_le_bytes());
+    data[NUM_SUCCESS_OFFSET..NUM_SUCCESS_OFFSET + 4].copy_from_slice(&num_success.to_le_bytes());
+    data[ROUND_OPEN_TIMESTAMP_OFFSET..ROUND_OPEN_TIMESTAMP_OFFSET + 8].copy_from_slice(&timestamp.to_le_bytes());
+    data[RESULT_MANTISSA_OFFSET..RESULT_MANTISSA_OFFSET + 16].copy_from_slice(&mantissa.to_le_bytes());
+    data[RESULT_SCALE_OFFSET..RESULT_SCALE_OFFSET + 4].copy_from_slice(&scale.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_switchboard_latest_price() {
+    let data = mock_aggregator_data(150_250, 3, 160, 3);
+    let price = SwitchboardAggregator::try_from_data(&data).unwrap().latest_price(0).unwrap();
+    assert_eq!(price, RawPrice { answer: 150_250, decimals: 3, timestamp: 160 });
+
+    // 没有成功响应的轮次被拒绝
+    let data = mock_aggregator_data(150_250, 3, 160, 0);
+    assert!(SwitchboardAggregator::try_from_data(&data).unwrap().latest_price(0).is_err());
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+use crate::{ LendingError, LendingPool, OracleType };
+use crate::oracle::read_raw_price;
+use crate::math::{ Decimal, TryDiv };
+
+// 固定价格在资金池中以 12 位小数保存（1.0 = 1_000_000_000_000）
+pub const PRICE_DECIMALS: u8 = 12;
+
+// 将预言机原始报价转换为 Decimal，拒绝非正数与过期报价
+pub fn normalize_price(
+    answer: i128,
+    decimals: u8,
+    timestamp: i64,
+    now: i64,
+    max_staleness: i64
+) -> Result<Decimal> {
+    require!(answer > 0, LendingError::InvalidOraclePrice);
+
+    let age = now.checked_sub(timestamp).ok_or(LendingError::MathOverflow)?;
+    require!(age <= max_staleness, LendingError::InvalidTimestamp);
+
+    // price = answer / 10^decimals
+    let unit = 10u128.checked_pow(decimals as u32).ok_or(LendingError::MathOverflow)?;
+    let price = Decimal::from_u128(answer as u128).try_div(Decimal::from_u128(unit))?;
+
+    // 精度截断后为零的报价同样视为无效
+    require!(!price.is_zero(), LendingError::InvalidOraclePrice);
+
+    Ok(price)
+}
+
+// 按资金池配置的预言机读取价格
+pub fn get_oracle_price(pool: &LendingPool, feed: &AccountInfo) -> Result<Decimal> {
+    read_oracle_price(pool, feed, Clock::get()?.unix_timestamp)
+}
+
+pub fn read_oracle_price(pool: &LendingPool, feed: &AccountInfo, now: i64) -> Result<Decimal> {
+    let data = feed.try_borrow_data()?;
+    read_oracle_price_data(pool, feed.owner, &data, now)
+}
+
+// 按 feed 账户的所有者与数据读取价格（链下客户端可直接传入获取到的账户）
+pub fn read_oracle_price_data(pool: &LendingPool, feed_owner: &Pubkey, data: &[u8], now: i64) -> Result<Decimal> {
+    let raw = if pool.oracle_type == OracleType::Fixed {
+        read_raw_price(pool.oracle_type, &[], pool.oracle_fixed_price, now)?
+    } else {
+        // feed 账户必须归配置的预言机程序所有
+        require_keys_eq!(*feed_owner, pool.oracle_program, LendingError::InvalidOraclePrice);
+        read_raw_price(pool.oracle_type, data, pool.oracle_fixed_price, now)?
+    };
+
+    normalize_price(raw.answer, raw.decimals, raw.timestamp, now, pool.max_staleness)
+}
+
+#[test]
+fn test_normalize_price() {
+    // SOL/USD：8 位小数，150.0
+    assert_eq!(normalize_price(15_000_000_000, 8, 100, 160, 60).unwrap(), Decimal::from(150u64));
+    // 18 位小数的报价
+    assert_eq!(normalize_price(1_000_000_000_000_000_000, 18, 100, 100, 60).unwrap(), Decimal::one());
+    // USDC/USD 与 SOL/USD 可直接相除，不会截断为零
+    let usdc = normalize_price(100_000_000, 8, 100, 100, 60).unwrap();
+    let sol = normalize_price(15_000_000_000, 8, 100, 100, 60).unwrap();
+    assert_eq!(usdc.try_div(sol).unwrap().to_string(), "0.006666666666666666");
+
+    // 负数、零与过期报价均被拒绝
+    assert!(normalize_price(-1, 8, 100, 100, 60).is_err());
+    assert!(normalize_price(0, 8, 100, 100, 60).is_err());
+    assert!(normalize_price(15_000_000_000, 8, 100, 161, 60).is_err());
+}
+
+#[test]
+fn test_read_oracle_price() {
+    let oracle_program = Pubkey::new_unique();
+    let feed_key = Pubkey::new_unique();
+    let mut pool = LendingPool {
+        oracle_type: OracleType::Pyth,
+        oracle_program,
+        oracle_feed: feed_key,
+        max_staleness: 60,
+        ..LendingPool::default()
+    };
+
+    let mut lamports = 0;
+    let mut data = crate::oracle::mock_price_update_data(
+        15_000_000_000,
+        -8,
+        100,
+        crate::oracle::VerificationLevel::Full
+    );
+    let feed = AccountInfo::new(&feed_key, false, false, &mut lamports, &mut data, &oracle_program, false, 0);
+    assert_eq!(read_oracle_price(&pool, &feed, 130).unwrap(), Decimal::from(150u64));
+    // 过期报价被拒绝
+    assert!(read_oracle_price(&pool, &feed, 161).is_err());
+
+    // feed 账户所有者不符时拒绝
+    pool.oracle_program = Pubkey::new_unique();
+    assert!(read_oracle_price(&pool, &feed, 130).is_err());
+
+    // 固定价格模式不读取 feed 账户
+    pool.oracle_type = OracleType::Fixed;
+    pool.oracle_fixed_price = 10u128.pow(PRICE_DECIMALS as u32);
+    assert_eq!(read_oracle_price(&pool, &feed, 1_000).unwrap(), Decimal::one());
+}
@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+
+use crate::{ LendingError, LendingPool, InterestRateModel, MAX_RATE_KINKS };
+use crate::math::{ Rate, Rounding, TryAdd, TryDiv, TryMul, TrySub };
+#[cfg(test)]
+use crate::RateKink;
+
+// 利率与利用率以 Rate（18 位小数）表示，配置参数使用基点（1% = 100）
+pub const BPS_SCALE: u64 = 10_000;
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+// 资金利用率 = 总借款 / 总供应（无供应时为 0，最高 100%）
+pub fn utilization_rate(pool: &LendingPool) -> Result<Rate> {
+    if pool.total_supply == 0 {
+        return Ok(Rate::zero());
+    }
+    let utilization = Rate::from_scaled_val(pool.total_borrowed as u128).try_div(
+        Rate::from_scaled_val(pool.total_supply as u128)
+    )?;
+    Ok(utilization.min(Rate::one()))
+}
+
+// 利率曲线的拐点（利用率基点, 年化利率基点），按利用率升序排列：
+// (0, base) -> (optimal, base + slope1) -> 额外拐点 -> (100%, base + slope1 + slope2)
+fn curve_points(model: &InterestRateModel) -> Vec<(u64, u64)> {
+    let base = model.base_rate_bps as u64;
+    let optimal_rate = base + (model.slope1_bps as u64);
+    let max_curve_rate = optimal_rate + (model.slope2_bps as u64);
+
+    let mut points = Vec::with_capacity(3 + MAX_RATE_KINKS);
+    points.push((0, base));
+    points.push((model.optimal_utilization_bps as u64, optimal_rate));
+    let kink_count = (model.extra_kink_count as usize).min(MAX_RATE_KINKS);
+    for kink in &model.extra_kinks[..kink_count] {
+        points.push((kink.utilization_bps as u64, kink.rate_bps as u64));
+    }
+    points.push((BPS_SCALE, max_curve_rate));
+    points
+}
+
+// 校验利率模型：拐点利用率严格递增、利率不递减，利率上限不低于基础利率
+pub fn validate_rate_model(model: &InterestRateModel) -> Result<()> {
+    require!(
+        model.optimal_utilization_bps > 0 && (model.optimal_utilization_bps as u64) < BPS_SCALE,
+        LendingError::InvalidPoolConfig
+    );
+    require!((model.extra_kink_count as usize) <= MAX_RATE_KINKS, LendingError::InvalidPoolConfig);
+    require!(model.max_rate_bps >= model.base_rate_bps, LendingError::InvalidPoolConfig);
+
+    let points = curve_points(model);
+    for pair in points.windows(2) {
+        let ((u0, r0), (u1, r1)) = (pair[0], pair[1]);
+        require!(u1 > u0 && r1 >= r0, LendingError::InvalidPoolConfig);
+    }
+    Ok(())
+}
+
+// 按利率曲线分段线性插值计算年化借款利率，不超过利率上限
+pub fn calculate_borrow_rate(pool: &LendingPool) -> Result<Rate> {
+    let model = &pool.rate_model;
+    let utilization = utilization_rate(pool)?;
+
+    let points = curve_points(model);
+    let mut rate = Rate::from_bps(points[points.len() - 1].1);
+    for pair in points.windows(2) {
+        let ((u0, r0), (u1, r1)) = (pair[0], pair[1]);
+        let (u0, u1) = (Rate::from_bps(u0), Rate::from_bps(u1));
+        if utilization <= u1 {
+            // rate = r0 + (r1 - r0) * (utilization - u0) / (u1 - u0)
+            let progress = utilization.try_sub(u0)?.try_div(u1.try_sub(u0)?)?;
+            rate = Rate::from_bps(r0).try_add(Rate::from_bps(r1 - r0).try_mul(progress)?)?;
+            break;
+        }
+    }
+
+    Ok(rate.min(Rate::from_bps(model.max_rate_bps as u64)))
+}
+
+// 年化存款利率 = 借款利率 * 利用率 * (1 - 储备金率)，向下取整
+pub fn calculate_supply_rate(pool: &LendingPool) -> Result<Rate> {
+    let borrow_rate = calculate_borrow_rate(pool)?;
+    let utilization = utilization_rate(pool)?;
+    let depositor_share = Rate::from_percent(100u8.saturating_sub(pool.reserve_factor));
+
+    borrow_rate.try_mul(utilization)?.try_mul(depositor_share)
+}
+
+// 按秒复利的增长系数：(1 + 年化利率 / 一年秒数)^经过秒数
+// 每秒利率与每步乘方均向上取整，保证借款人计息不少于精确值
+pub fn compound_interest_factor(annual_rate: Rate, elapsed: u64) -> Result<Rate> {
+    let per_second = Rate::from_scaled_val(
+        annual_rate.to_scaled_val().div_ceil(SECONDS_PER_YEAR as u128)
+    );
+    Rate::one().try_add(per_second)?.try_pow(elapsed, Rounding::Up)
+}
+
+// 单利增长系数：1 + 年化利率 * 经过秒数 / 一年秒数，向下取整
+pub fn linear_interest_factor(annual_rate: Rate, elapsed: u64) -> Result<Rate> {
+    Rate::one().try_add(annual_rate.try_mul(elapsed)?.try_div(SECONDS_PER_YEAR)?)
+}
+
+#[cfg(test)]
+fn test_pool(total_supply: u64, total_borrowed: u64) -> LendingPool {
+    LendingPool {
+        total_supply,
+        total_borrowed,
+        reserve_factor: 10,
+        rate_model: InterestRateModel {
+            optimal_utilization_bps: 8_000, // 80%
+            base_rate_bps: 200, // 2%
+            slope1_bps: 800, // 0-80%：2% -> 10%
+            slope2_bps: 10_000, // 80-100%：10% -> 110%
+            max_rate_bps: 5_000, // 上限 50%
+            ..InterestRateModel::default()
+        },
+        ..LendingPool::default()
+    }
+}
+
+#[test]
+fn test_utilization_rate() {
+    // 无供应时利用率为 0，不会除零
+    assert_eq!(utilization_rate(&test_pool(0, 0)).unwrap(), Rate::zero());
+    assert_eq!(utilization_rate(&test_pool(1_000, 500)).unwrap(), Rate::from_percent(50));
+    // 利用率最高按 100% 计
+    assert_eq!(utilization_rate(&test_pool(1_000, 2_000)).unwrap(), Rate::one());
+}
+
+#[test]
+fn test_calculate_borrow_rate() {
+    // 0%：基础利率 2%
+    assert_eq!(calculate_borrow_rate(&test_pool(1_000, 0)).unwrap(), Rate::from_percent(2));
+    // 40%：2% + 8% * 40/80 = 6%
+    assert_eq!(calculate_borrow_rate(&test_pool(1_000, 400)).unwrap(), Rate::from_percent(6));
+    // 80%：最优利用率处 10%
+    assert_eq!(calculate_borrow_rate(&test_pool(1_000, 800)).unwrap(), Rate::from_percent(10));
+    // 90%：10% + 100% * 10/20 = 60%，受上限限制为 50%
+    assert_eq!(calculate_borrow_rate(&test_pool(1_000, 900)).unwrap(), Rate::from_percent(50));
+
+    // 额外拐点：90% 处利率 20%
+    let mut pool = test_pool(1_000, 850);
+    pool.rate_model.extra_kinks[0] = RateKink { utilization_bps: 9_000, rate_bps: 2_000 };
+    pool.rate_model.extra_kink_count = 1;
+    assert!(validate_rate_model(&pool.rate_model).is_ok());
+    // 85%：10% + 10% * 5/10 = 15%
+    assert_eq!(calculate_borrow_rate(&pool).unwrap(), Rate::from_percent(15));
+}
+
+#[test]
+fn test_calculate_supply_rate() {
+    // 40% 利用率：6% * 40% * 90% = 2.16%
+    assert_eq!(calculate_supply_rate(&test_pool(1_000, 400)).unwrap(), Rate::from_bps(216));
+    assert_eq!(calculate_supply_rate(&test_pool(0, 0)).unwrap(), Rate::zero());
+}
+
+#[test]
+fn test_interest_factors() {
+    // 零利率或零时间不增长
+    assert_eq!(compound_interest_factor(Rate::zero(), 1_000).unwrap(), Rate::one());
+    assert_eq!(compound_interest_factor(Rate::from_percent(10), 0).unwrap(), Rate::one());
+    assert_eq!(linear_interest_factor(Rate::from_percent(10), 0).unwrap(), Rate::one());
+
+    // 10% APR 持续一年：单利 1.1，复利约 e^0.1 = 1.10517
+    let rate = Rate::from_percent(10);
+    assert_eq!(linear_interest_factor(rate, SECONDS_PER_YEAR).unwrap(), Rate::from_percent(110));
+    let compound = compound_interest_factor(rate, SECONDS_PER_YEAR).unwrap();
+    assert!(compound > Rate::from_bps(11_051) && compound < Rate::from_bps(11_052));
+
+    // 分两次结算与一次结算的结果一致（误差仅来自取整）
+    let half = compound_interest_factor(rate, SECONDS_PER_YEAR / 2).unwrap();
+    let twice = half.try_mul_round(half, Rounding::Up).unwrap();
+    assert!(twice.to_scaled_val().abs_diff(compound.to_scaled_val()) < 1_000);
+}
+
+#[test]
+fn test_validate_rate_model() {
+    let mut model = test_pool(0, 0).rate_model;
+    assert!(validate_rate_model(&model).is_ok());
+
+    // 额外拐点必须位于最优利用率之后
+    model.extra_kinks[0] = RateKink { utilization_bps: 7_000, rate_bps: 2_000 };
+    model.extra_kink_count = 1;
+    assert!(validate_rate_model(&model).is_err());
+
+    // 拐点利率不能下降
+    model.extra_kinks[0] = RateKink { utilization_bps: 9_000, rate_bps: 500 };
+    assert!(validate_rate_model(&model).is_err());
+
+    model.extra_kink_count = 0;
+    model.optimal_utilization_bps = 10_000;
+    assert!(validate_rate_model(&model).is_err());
+}
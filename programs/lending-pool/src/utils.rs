@@ -1,131 +1,1304 @@
 use anchor_lang::prelude::Result;
-use crate::{ LendingError, LendingPool, Borrow };
+use crate::{
+    LendingError,
+    LendingPool,
+    PoolStateSnapshot,
+    InterestAccruedEvent,
+    OracleType,
+    UserPosition,
+    Obligation,
+    MAX_OBLIGATION_POSITIONS,
+    Borrow,
+    Withdraw,
+    SetCollateralEnabled,
+    WithdrawReceipts,
+};
 use anchor_lang::prelude::*;
-use chainlink_solana as chainlink;
-
-// 计算用户健康因子
-pub fn calculate_health_factor(
-    deposited_amount: u64,
-    borrowed_amount: u64,
-    collateral_factor: u8,
-    price: u64 // 假设 price 是抵押品价格（如 1e6 表示 1.0 USD）
+use anchor_lang::Discriminator;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked,
+    load_instruction_at_checked,
+};
+use crate::health::{ calculate_health_factor, HealthFactor, HealthStatus };
+use crate::price::get_oracle_price;
+use crate::rate::{
+    calculate_borrow_rate,
+    calculate_supply_rate,
+    compound_interest_factor,
+    utilization_rate,
+    linear_interest_factor,
+    validate_rate_model,
+};
+use crate::math::{ Decimal, Rate, Rounding, TryAdd, TryDiv, TryMul, TrySub };
+
+// 代币最小单位换算：1 个代币 = 10^decimals
+fn token_unit(decimals: u8) -> Result<Decimal> {
+    let unit = 10u64.checked_pow(decimals as u32).ok_or(LendingError::MathOverflow)?;
+    Ok(Decimal::from(unit))
+}
+
+// 抵押价值的加权方式：借款/取款等操作按最高借款比例，清算按清算阈值
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CollateralWeight {
+    LoanToValue,
+    LiquidationThreshold,
+}
+
+impl CollateralWeight {
+    fn percent(self, pool: &LendingPool) -> u8 {
+        match self {
+            CollateralWeight::LoanToValue => pool.loan_to_value,
+            CollateralWeight::LiquidationThreshold => pool.liquidation_threshold,
+        }
+    }
+}
+
+// 单个资产的估值：(加权抵押价值, 债务价值)，按该资金池预言机报价计价
+// 抵押价值向下取整，债务价值向上取整
+pub fn asset_values(
+    collateral_amount: u64,
+    debt_amount: u64,
+    pool: &LendingPool,
+    price: Decimal,
+    weight: CollateralWeight
+) -> Result<(Decimal, Decimal)> {
+    let unit = token_unit(pool.decimals)?;
+
+    let weighted_collateral_value = Decimal::from(collateral_amount)
+        .try_mul(price)?
+        .try_div(unit)?
+        .try_mul(Decimal::from_percent(weight.percent(pool)))?;
+
+    let debt_value = Decimal::from(debt_amount)
+        .try_mul_round(price, Rounding::Up)?
+        .try_div_round(unit, Rounding::Up)?;
+
+    Ok((weighted_collateral_value, debt_value))
+}
+
+// 义务账户估值汇总：抵押价值同时按最高借款比例与清算阈值加权，健康判断统一经由 HealthStatus
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ObligationValues {
+    pub borrow_collateral_value: Decimal, // 按最高借款比例加权
+    pub liquidation_collateral_value: Decimal, // 按清算阈值加权
+    pub debt_value: Decimal,
+}
+
+impl ObligationValues {
+    // 累加一个仓位的估值
+    pub fn add_position(&mut self, collateral_amount: u64, debt_amount: u64, pool: &LendingPool, price: Decimal) -> Result<()> {
+        let (borrow_value, debt_value) = asset_values(collateral_amount, debt_amount, pool, price, CollateralWeight::LoanToValue)?;
+        let (liquidation_value, _) = asset_values(collateral_amount, 0, pool, price, CollateralWeight::LiquidationThreshold)?;
+
+        self.borrow_collateral_value = self.borrow_collateral_value.try_add(borrow_value)?;
+        self.liquidation_collateral_value = self.liquidation_collateral_value.try_add(liquidation_value)?;
+        self.debt_value = self.debt_value.try_add(debt_value)?;
+        Ok(())
+    }
+
+    pub fn borrow_health(&self) -> Result<HealthFactor> {
+        calculate_health_factor(self.borrow_collateral_value, self.debt_value)
+    }
+
+    pub fn liquidation_health(&self) -> Result<HealthFactor> {
+        calculate_health_factor(self.liquidation_collateral_value, self.debt_value)
+    }
+
+    pub fn status(&self) -> Result<HealthStatus> {
+        Ok(HealthStatus::new(self.borrow_health()?, self.liquidation_health()?))
+    }
+}
+
+#[cfg(test)]
+use crate::{ InterestRateModel, PAUSE_BORROW, PAUSE_FLASH_LOAN, PAUSE_LIQUIDATE, PAUSE_REPAY };
+
+#[test]
+fn test_asset_values() {
+    let pool = LendingPool {
+        decimals: 6,
+        loan_to_value: 75,
+        liquidation_threshold: 80,
+        ..LendingPool::default()
+    };
+
+    // 100 个代币（精度 6），单价 2.0，最高借款比例 75%
+    let (collateral_value, debt_value) = asset_values(
+        100_000_000,
+        50_000_000,
+        &pool,
+        Decimal::from(2u64),
+        CollateralWeight::LoanToValue
+    ).unwrap();
+    assert_eq!(collateral_value, Decimal::from(150u64));
+    assert_eq!(debt_value, Decimal::from(100u64));
+    assert_eq!(
+        calculate_health_factor(collateral_value, debt_value).unwrap(),
+        HealthFactor(Rate::one().to_scaled_val() * 3 / 2)
+    );
+
+    // 清算按 80% 阈值加权
+    let (collateral_value, _) = asset_values(
+        100_000_000,
+        0,
+        &pool,
+        Decimal::from(2u64),
+        CollateralWeight::LiquidationThreshold
+    ).unwrap();
+    assert_eq!(collateral_value, Decimal::from(160u64));
+}
+
+// 指数以 18 位小数的原始值保存在资金池中（1.0 = Rate::one()）
+// 份额 -> 实际数量：scaled * index
+pub fn scaled_to_amount(scaled: u64, index: u128, rounding: Rounding) -> Result<u64> {
+    Decimal::from(scaled)
+        .try_mul_round(Decimal::from_scaled_val(index), rounding)?
+        .try_round_u64(rounding)
+}
+
+// 实际数量 -> 份额：amount / index
+pub fn amount_to_scaled(amount: u64, index: u128, rounding: Rounding) -> Result<u64> {
+    Decimal::from(amount)
+        .try_div_round(Decimal::from_scaled_val(index), rounding)?
+        .try_round_u64(rounding)
+}
+
+// 用户当前存款（含已累计利息，向下取整）
+pub fn deposited_amount(user_position: &UserPosition, pool: &LendingPool) -> Result<u64> {
+    scaled_to_amount(user_position.scaled_deposited_amount, pool.liquidity_index, Rounding::Down)
+}
+
+// 用户当前借款（含已累计利息，向上取整）
+pub fn borrowed_amount(user_position: &UserPosition, pool: &LendingPool) -> Result<u64> {
+    scaled_to_amount(user_position.scaled_borrowed_amount, pool.borrow_index, Rounding::Up)
+}
+
+// 计入抵押的存款数量（未启用抵押的仓位不计入）
+pub fn collateral_amount(user_position: &UserPosition, pool: &LendingPool) -> Result<u64> {
+    if user_position.collateral_enabled {
+        deposited_amount(user_position, pool)
+    } else {
+        Ok(0)
+    }
+}
+
+// 取出 amount 需扣减的存款份额（向上取整，全部取出时返回全部份额）
+pub fn scaled_withdraw_amount(
+    user_position: &UserPosition,
+    pool: &LendingPool,
+    amount: u64
 ) -> Result<u64> {
-    let collateral_value = deposited_amount.checked_mul(price).ok_or(LendingError::MathOverflow)?;
+    require!(
+        amount <= deposited_amount(user_position, pool)?,
+        LendingError::InsufficientBalance
+    );
+    let scaled = amount_to_scaled(amount, pool.liquidity_index, Rounding::Up)?;
+    Ok(scaled.min(user_position.scaled_deposited_amount))
+}
 
-    let borrow_limit =
-        collateral_value.checked_mul(collateral_factor as u64).ok_or(LendingError::MathOverflow)? /
-        100;
+// 偿还 amount 需扣减的借款份额（向下取整，还清时返回全部份额）
+pub fn scaled_repay_amount(
+    user_position: &UserPosition,
+    pool: &LendingPool,
+    amount: u64
+) -> Result<u64> {
+    if amount >= borrowed_amount(user_position, pool)? {
+        return Ok(user_position.scaled_borrowed_amount);
+    }
+    amount_to_scaled(amount, pool.borrow_index, Rounding::Down)
+}
 
-    if borrow_limit == 0 {
-        return Ok(0);
+// 将仓位登记到义务账户（已登记则跳过）
+pub fn register_position(
+    obligation: &mut Obligation,
+    owner: Pubkey,
+    position_key: Pubkey
+) -> Result<()> {
+    obligation.owner = owner;
+
+    let count = obligation.position_count as usize;
+    if obligation.positions[..count].contains(&position_key) {
+        return Ok(());
+    }
+
+    require!(count < MAX_OBLIGATION_POSITIONS, LendingError::ObligationFull);
+    obligation.positions[count] = position_key;
+    obligation.position_count += 1;
+
+    Ok(())
+}
+
+// 仓位清空（无存款且无借款）后从义务账户中移除
+pub fn release_position(
+    obligation: &mut Obligation,
+    position_key: Pubkey,
+    user_position: &UserPosition
+) {
+    if user_position.scaled_deposited_amount != 0 || user_position.scaled_borrowed_amount != 0 {
+        return;
+    }
+
+    let count = obligation.position_count as usize;
+    if let Some(index) = obligation.positions[..count].iter().position(|key| *key == position_key) {
+        obligation.positions[index] = obligation.positions[count - 1];
+        obligation.positions[count - 1] = Pubkey::default();
+        obligation.position_count -= 1;
+    }
+}
+
+#[test]
+fn test_register_and_release_position() {
+    let mut obligation = Obligation::default();
+    let owner = Pubkey::new_unique();
+    let first = Pubkey::new_unique();
+    let second = Pubkey::new_unique();
+
+    register_position(&mut obligation, owner, first).unwrap();
+    register_position(&mut obligation, owner, second).unwrap();
+    // 重复登记不会增加仓位
+    register_position(&mut obligation, owner, first).unwrap();
+    assert_eq!(obligation.position_count, 2);
+
+    // 仍有余额的仓位不会被移除
+    let open_position = UserPosition {
+        scaled_borrowed_amount: 1,
+        ..UserPosition::default()
+    };
+    release_position(&mut obligation, first, &open_position);
+    assert_eq!(obligation.position_count, 2);
+
+    release_position(&mut obligation, first, &UserPosition::default());
+    assert_eq!(obligation.position_count, 1);
+    assert_eq!(obligation.positions[0], second);
+
+    // 超过上限时拒绝登记
+    for _ in 1..MAX_OBLIGATION_POSITIONS {
+        register_position(&mut obligation, owner, Pubkey::new_unique()).unwrap();
+    }
+    assert!(register_position(&mut obligation, owner, Pubkey::new_unique()).is_err());
+}
+
+// 从 remaining_accounts 中读取本程序拥有的账户
+fn load_program_account<T: AccountDeserialize + Owner>(info: &AccountInfo) -> Result<T> {
+    require_keys_eq!(*info.owner, T::owner(), LendingError::InvalidObligationAccounts);
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+// 仓位是否仍有可被清算没收的存款（未启用抵押的存款不计入健康因子，清算无法没收）
+pub fn has_seizable_deposits(user_position: &UserPosition) -> bool {
+    user_position.collateral_enabled && user_position.scaled_deposited_amount != 0
+}
+
+// 义务账户中除 exclude 外的仓位均无可没收的存款
+// remaining_accounts 为这些用户仓位，顺序与义务账户中的仓位一致
+pub fn require_no_deposits<'info>(
+    obligation: &Obligation,
+    exclude: Pubkey,
+    remaining_accounts: &[AccountInfo<'info>]
+) -> Result<()> {
+    let positions: Vec<&Pubkey> = obligation.positions[..obligation.position_count as usize]
+        .iter()
+        .filter(|key| **key != exclude)
+        .collect();
+    require!(
+        remaining_accounts.len() == positions.len(),
+        LendingError::InvalidObligationAccounts
+    );
+
+    for (position_key, account) in positions.into_iter().zip(remaining_accounts) {
+        require_keys_eq!(account.key(), *position_key, LendingError::InvalidObligationAccounts);
+        let user_position = load_program_account::<UserPosition>(account)?;
+        require!(!has_seizable_deposits(&user_position), LendingError::ObligationHasDeposits);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_require_no_deposits() {
+    use anchor_lang::AccountSerialize;
+
+    let owner = Pubkey::new_unique();
+    let settled = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let mut obligation = Obligation::default();
+    register_position(&mut obligation, owner, settled).unwrap();
+    register_position(&mut obligation, owner, other).unwrap();
+
+    let check = |position: UserPosition| {
+        let mut data = Vec::new();
+        position.try_serialize(&mut data).unwrap();
+        let mut lamports = 0;
+        let program_id = crate::ID;
+        let info = AccountInfo::new(&other, false, false, &mut lamports, &mut data, &program_id, false, 0);
+        require_no_deposits(&obligation, settled, &[info]).is_ok()
+    };
+
+    // 未启用抵押的零星存款无法被清算，不阻止核销
+    let dust = UserPosition { scaled_deposited_amount: 1, collateral_enabled: false, ..UserPosition::default() };
+    assert!(check(dust));
+
+    // 已启用抵押的存款需先清算
+    let collateral = UserPosition { scaled_deposited_amount: 1, collateral_enabled: true, ..UserPosition::default() };
+    assert!(!check(collateral));
+}
+
+// 在 current 的基础上累加义务账户中其余仓位的估值
+// remaining_accounts 按 [资金池, 用户仓位, 价格 feed] 三个一组，顺序与义务账户中的仓位一致
+pub fn obligation_values<'info>(
+    obligation: &Obligation,
+    exclude: &[Pubkey],
+    current: ObligationValues,
+    remaining_accounts: &[AccountInfo<'info>]
+) -> Result<ObligationValues> {
+    let positions: Vec<&Pubkey> = obligation.positions[..obligation.position_count as usize]
+        .iter()
+        .filter(|key| !exclude.contains(key))
+        .collect();
+    require!(
+        remaining_accounts.len() == positions.len() * 3,
+        LendingError::InvalidObligationAccounts
+    );
+
+    let mut values = current;
+    for (position_key, accounts) in positions.into_iter().zip(remaining_accounts.chunks(3)) {
+        let mut pool = load_program_account::<LendingPool>(&accounts[0])?;
+        let user_position = load_program_account::<UserPosition>(&accounts[1])?;
+        require_keys_eq!(accounts[1].key(), *position_key, LendingError::InvalidObligationAccounts);
+        require_keys_eq!(user_position.pool, accounts[0].key(), LendingError::InvalidObligationAccounts);
+        // 每个资金池只能使用其配置的预言机
+        require_keys_eq!(accounts[2].key(), pool.oracle_feed, LendingError::InvalidOraclePrice);
+
+        // 其他资金池可能许久未更新：仅在内存中结算利息到当前时间（不写回账户），避免低估债务
+        accrue_pool_interest(&mut pool, Clock::get()?.unix_timestamp)?;
+
+        let price = get_oracle_price(&pool, &accounts[2])?;
+        values.add_position(
+            collateral_amount(&user_position, &pool)?,
+            borrowed_amount(&user_position, &pool)?,
+            &pool,
+            price
+        )?;
+    }
+
+    Ok(values)
+}
+
+// 要求状态为 Healthy（BorrowLimited 同样拒绝），返回按最高借款比例加权的健康因子
+fn require_healthy(values: &ObligationValues) -> Result<HealthFactor> {
+    require!(values.status()? == HealthStatus::Healthy, LendingError::InsufficientCollateral);
+
+    values.borrow_health()
+}
+
+#[test]
+fn test_obligation_values_status() {
+    let pool = LendingPool { decimals: 6, loan_to_value: 75, liquidation_threshold: 80, ..LendingPool::default() };
+    let price = Decimal::from(1u64);
+
+    // 抵押 100、债务 70：Healthy
+    let mut values = ObligationValues::default();
+    values.add_position(100_000_000, 70_000_000, &pool, price).unwrap();
+    assert_eq!(values.status().unwrap(), HealthStatus::Healthy);
+    assert!(require_healthy(&values).is_ok());
+
+    // 债务 78：超过最高借款比例但未达清算阈值，拒绝借款/取款
+    let mut values = ObligationValues::default();
+    values.add_position(100_000_000, 78_000_000, &pool, price).unwrap();
+    assert_eq!(values.status().unwrap(), HealthStatus::BorrowLimited);
+    assert!(require_healthy(&values).is_err());
+
+    // 债务 85：可清算
+    let mut values = ObligationValues::default();
+    values.add_position(100_000_000, 85_000_000, &pool, price).unwrap();
+    assert_eq!(values.status().unwrap(), HealthStatus::Liquidatable);
+}
+
+// 记录仓位归属及当前指数快照
+pub fn update_position_snapshot(
+    user_position: &mut UserPosition,
+    user: Pubkey,
+    pool_key: Pubkey,
+    pool: &LendingPool
+) -> Result<()> {
+    user_position.user = user;
+    user_position.pool = pool_key;
+    user_position.liquidity_index_snapshot = pool.liquidity_index;
+    user_position.borrow_index_snapshot = pool.borrow_index;
+    user_position.last_update_time = Clock::get()?.unix_timestamp;
+    Ok(())
+}
+
+#[test]
+fn test_collateral_amount() {
+    let pool = LendingPool {
+        liquidity_index: Rate::from_percent(110).to_scaled_val(),
+        borrow_index: Rate::one().to_scaled_val(),
+        ..LendingPool::default()
+    };
+    let mut user_position = UserPosition {
+        scaled_deposited_amount: 1_000,
+        ..UserPosition::default()
+    };
+
+    // 未启用抵押时不计入
+    assert_eq!(collateral_amount(&user_position, &pool).unwrap(), 0);
+
+    // 启用后按流动性指数折算（含利息）
+    user_position.collateral_enabled = true;
+    assert_eq!(collateral_amount(&user_position, &pool).unwrap(), 1_100);
+}
+
+#[test]
+fn test_scaled_amount_rounding() {
+    let index = Rate::from_percent(150).to_scaled_val();
+
+    // 存款份额向下取整，借款份额向上取整
+    assert_eq!(amount_to_scaled(100, index, Rounding::Down).unwrap(), 66);
+    assert_eq!(amount_to_scaled(100, index, Rounding::Up).unwrap(), 67);
+    assert_eq!(scaled_to_amount(67, index, Rounding::Down).unwrap(), 100);
+    assert_eq!(scaled_to_amount(67, index, Rounding::Up).unwrap(), 101);
+    // 恰好整除时两种方向结果一致
+    assert_eq!(amount_to_scaled(150, index, Rounding::Up).unwrap(), 100);
+}
+
+// 操作被暂停时拒绝执行
+pub fn require_not_paused(pool: &LendingPool, operation: u8) -> Result<()> {
+    require!(pool.paused_operations & operation == 0, LendingError::OperationPaused);
+    Ok(())
+}
+
+#[test]
+fn test_require_not_paused() {
+    let pool = LendingPool {
+        paused_operations: PAUSE_BORROW | PAUSE_FLASH_LOAN,
+        ..LendingPool::default()
+    };
+    assert!(require_not_paused(&pool, PAUSE_BORROW).is_err());
+    assert!(require_not_paused(&pool, PAUSE_FLASH_LOAN).is_err());
+    // 其余操作不受影响
+    assert!(require_not_paused(&pool, PAUSE_REPAY).is_ok());
+    assert!(require_not_paused(&pool, PAUSE_LIQUIDATE).is_ok());
+}
+
+// 存款后总供应不得超过供应上限（0 表示不限）
+pub fn check_supply_cap(pool: &LendingPool, amount: u64) -> Result<()> {
+    let total_supply = pool.total_supply.checked_add(amount).ok_or(LendingError::MathOverflow)?;
+    require!(
+        pool.supply_cap == 0 || total_supply <= pool.supply_cap,
+        LendingError::SupplyCapExceeded
+    );
+    Ok(())
+}
+
+// 借款后总借款不得超过借款上限（0 表示不限）
+pub fn check_borrow_cap(pool: &LendingPool, amount: u64) -> Result<()> {
+    let total_borrowed = pool.total_borrowed.checked_add(amount).ok_or(LendingError::MathOverflow)?;
+    require!(
+        pool.borrow_cap == 0 || total_borrowed <= pool.borrow_cap,
+        LendingError::BorrowCapExceeded
+    );
+    Ok(())
+}
+
+#[test]
+fn test_supply_and_borrow_caps() {
+    let mut pool = LendingPool {
+        total_supply: 900,
+        total_borrowed: 400,
+        ..LendingPool::default()
+    };
+
+    // 未设置上限时不限制
+    assert!(check_supply_cap(&pool, 1_000_000).is_ok());
+    assert!(check_borrow_cap(&pool, 1_000_000).is_ok());
+
+    pool.supply_cap = 1_000;
+    pool.borrow_cap = 500;
+    assert!(check_supply_cap(&pool, 100).is_ok());
+    assert!(check_supply_cap(&pool, 101).is_err());
+    assert!(check_borrow_cap(&pool, 100).is_ok());
+    assert!(check_borrow_cap(&pool, 101).is_err());
+}
+
+// 借款前的上限与健康检查，返回计入本次借款后的健康因子
+pub fn check_before_borrow<'info>(
+    ctx: &Context<'_, '_, '_, 'info, Borrow<'info>>,
+    amount: u64
+) -> Result<HealthFactor> {
+    let user_position = &ctx.accounts.user_position;
+    let pool = &ctx.accounts.pool;
+
+    check_borrow_cap(pool, amount)?;
+
+    // 当前资金池按自身预言机估值（计入本次借款）
+    let price = get_oracle_price(pool, &ctx.accounts.price_feed)?;
+    let borrowed = borrowed_amount(user_position, pool)?
+        .checked_add(amount)
+        .ok_or(LendingError::MathOverflow)?;
+    let mut current = ObligationValues::default();
+    current.add_position(collateral_amount(user_position, pool)?, borrowed, pool, price)?;
+
+    // 汇总义务账户中其他资金池的仓位
+    let values = obligation_values(&ctx.accounts.obligation, &[user_position.key()], current, ctx.remaining_accounts)?;
+
+    require_healthy(&values)
+}
+
+// 取款前的流动性与健康检查
+pub fn check_before_withdraw<'info>(ctx: &Context<'_, '_, '_, 'info, Withdraw<'info>>, amount: u64) -> Result<()> {
+    let user_position = &ctx.accounts.user_position;
+    let pool = &ctx.accounts.pool;
+
+    // 可用流动性 = 总供应量 - 总借款
+    let available_liquidity = pool.total_supply
+        .checked_sub(pool.total_borrowed)
+        .ok_or(LendingError::MathOverflow)?;
+    require!(amount <= available_liquidity, LendingError::InsufficientLiquidity);
+
+    // 未作为抵押的存款不影响健康因子
+    if !user_position.collateral_enabled {
+        return Ok(());
     }
 
-    let health_factor =
-        borrow_limit.checked_mul(100).ok_or(LendingError::MathOverflow)? / borrowed_amount;
+    // 取款后义务账户整体仍需保持健康
+    let price = get_oracle_price(pool, &ctx.accounts.price_feed)?;
+    let remaining_deposit = deposited_amount(user_position, pool)?
+        .checked_sub(amount)
+        .ok_or(LendingError::MathOverflow)?;
+    let mut current = ObligationValues::default();
+    current.add_position(remaining_deposit, borrowed_amount(user_position, pool)?, pool, price)?;
+
+    let values = obligation_values(&ctx.accounts.obligation, &[user_position.key()], current, ctx.remaining_accounts)?;
 
-    Ok(health_factor)
+    require_healthy(&values)?;
+
+    Ok(())
 }
 
-// 从预言机获取价格
-pub fn get_oracle_price<'info>(feed_program:&AccountInfo<'info>,deposit_feed:&AccountInfo<'info>,borrow_feed:&AccountInfo<'info>,) -> Result<u64> {
-    // 从预言机获取价格
-    let deposit_feed = chainlink::latest_round_data(feed_program.to_account_info(), deposit_feed.to_account_info())?.answer as u64;
-    let borrow_feed = chainlink::latest_round_data(feed_program.to_account_info(), borrow_feed.to_account_info())?.answer as u64;
+// 转出存款凭证前的健康检查（凭证仍在流通，无需检查可用流动性）
+pub fn check_before_withdraw_receipts<'info>(
+    ctx: &Context<'_, '_, '_, 'info, WithdrawReceipts<'info>>,
+    shares: u64
+) -> Result<()> {
+    let user_position = &ctx.accounts.user_position;
+    let pool = &ctx.accounts.pool;
+
+    let remaining_shares = user_position.scaled_deposited_amount
+        .checked_sub(shares)
+        .ok_or(LendingError::InsufficientBalance)?;
+
+    // 未作为抵押的存款不影响健康因子
+    if !user_position.collateral_enabled {
+        return Ok(());
+    }
+
+    // 转出后义务账户整体仍需保持健康
+    let price = get_oracle_price(pool, &ctx.accounts.price_feed)?;
+    let remaining_deposit = scaled_to_amount(remaining_shares, pool.liquidity_index, Rounding::Down)?;
+    let mut current = ObligationValues::default();
+    current.add_position(remaining_deposit, borrowed_amount(user_position, pool)?, pool, price)?;
+
+    let values = obligation_values(&ctx.accounts.obligation, &[user_position.key()], current, ctx.remaining_accounts)?;
+
+    require_healthy(&values)?;
 
-  
-    // 计算相对价格，比如：SOL/USDC 价格
-    let relative_price = deposit_feed
-        .checked_div(borrow_feed)
-        .ok_or(ProgramError::InvalidArgument)?;
-    Ok(relative_price)
+    Ok(())
 }
 
-// 借款前的健康检查
-pub fn check_before_borrow(ctx: &Context<Borrow>, amount: u64) -> Result<()> {
+// 停用抵押前的健康检查，返回停用后的健康因子（本就未启用时为 None）
+pub fn check_before_disable_collateral<'info>(
+    ctx: &Context<'_, '_, '_, 'info, SetCollateralEnabled<'info>>
+) -> Result<Option<HealthFactor>> {
     let user_position = &ctx.accounts.user_position;
     let pool = &ctx.accounts.pool;
-    let chainlink = &ctx.accounts.feed_program;
-    let depodit_feed = &ctx.accounts.depodit_feed;
-    let borrow_feed = &ctx.accounts.borrow_feed;
-    // 获取抵押品价格（预言机）
-    let price = get_oracle_price(chainlink,depodit_feed,borrow_feed)?;
-
-    let health_factor = calculate_health_factor(
-        user_position.deposited_amount,
-        user_position.borrowed_amount + amount,
-        pool.collateral_factor,
+
+    // 本就未启用抵押时无需检查
+    if !user_position.collateral_enabled {
+        return Ok(None);
+    }
+
+    // 停用后该仓位不再提供任何抵押
+    let price = get_oracle_price(pool, &ctx.accounts.price_feed)?;
+    let mut current = ObligationValues::default();
+    current.add_position(0, borrowed_amount(user_position, pool)?, pool, price)?;
+
+    let values = obligation_values(&ctx.accounts.obligation, &[user_position.key()], current, ctx.remaining_accounts)?;
+
+    require_healthy(&values).map(Some)
+}
+
+// 清算前检查：义务账户整体状态需为 Liquidatable，返回按清算阈值加权的健康因子
+// positions 为指令直接传入的仓位 (仓位地址, 仓位, 资金池, 价格)，同一资金池清算时只有一个
+pub fn check_before_liquidate<'info>(
+    obligation: &Obligation,
+    positions: &[(Pubkey, &UserPosition, &LendingPool, Decimal)],
+    remaining_accounts: &[AccountInfo<'info>]
+) -> Result<HealthFactor> {
+    let mut current = ObligationValues::default();
+    for (_, user_position, pool, price) in positions {
+        current.add_position(collateral_amount(user_position, pool)?, borrowed_amount(user_position, pool)?, pool, *price)?;
+    }
+
+    let exclude: Vec<Pubkey> = positions.iter().map(|(key, ..)| *key).collect();
+    let values = obligation_values(obligation, &exclude, current, remaining_accounts)?;
+    require!(values.status()? == HealthStatus::Liquidatable, LendingError::NotLiquidatable);
+
+    values.liquidation_health()
+}
+
+// 清算偿还：扣减仓位借款份额与资金池总借款
+pub fn apply_liquidation_repay(
+    user_position: &mut UserPosition,
+    pool: &mut LendingPool,
+    repay_amount: u64
+) -> Result<()> {
+    let scaled_repay = scaled_repay_amount(user_position, pool, repay_amount)?;
+    user_position.scaled_borrowed_amount = user_position.scaled_borrowed_amount
+        .checked_sub(scaled_repay)
+        .ok_or(LendingError::MathOverflow)?;
+    pool.total_borrowed = pool.total_borrowed.saturating_sub(repay_amount);
+    Ok(())
+}
+
+// 清算没收：扣减仓位存款份额与资金池总供应，返回扣减的份额（需销毁等量托管凭证）
+pub fn apply_liquidation_seize(
+    user_position: &mut UserPosition,
+    pool: &mut LendingPool,
+    seize_amount: u64
+) -> Result<u64> {
+    let scaled_seize = scaled_withdraw_amount(user_position, pool, seize_amount)?;
+    user_position.scaled_deposited_amount = user_position.scaled_deposited_amount
+        .checked_sub(scaled_seize)
+        .ok_or(LendingError::MathOverflow)?;
+    pool.total_supply = pool.total_supply
+        .checked_sub(seize_amount)
+        .ok_or(LendingError::MathOverflow)?;
+    Ok(scaled_seize)
+}
+
+#[test]
+fn test_same_pool_liquidation() {
+    let one = Rate::one().to_scaled_val();
+    let mut pool = LendingPool {
+        decimals: 6,
+        total_supply: 1_000_000_000,
+        total_borrowed: 500_000_000,
+        liquidity_index: one,
+        borrow_index: one,
+        loan_to_value: 75,
+        liquidation_threshold: 80,
+        liquidation_bonus: 5,
+        close_factor: 50,
+        ..LendingPool::default()
+    };
+    // 同一资金池中抵押 100、借款 85：85 > 100 * 80%，可被清算
+    let position_key = Pubkey::new_unique();
+    let mut position = UserPosition {
+        scaled_deposited_amount: 100_000_000,
+        scaled_borrowed_amount: 85_000_000,
+        collateral_enabled: true,
+        ..UserPosition::default()
+    };
+    let mut obligation = Obligation::default();
+    register_position(&mut obligation, Pubkey::new_unique(), position_key).unwrap();
+
+    let price = Decimal::one();
+    let health_factor = check_before_liquidate(&obligation, &[(position_key, &position, &pool, price)], &[]).unwrap();
+    assert!(!health_factor.is_healthy());
+
+    // 偿还与没收针对同一仓位、同一资金池
+    let (repay, seize) = calculate_liquidation_amounts(
+        100_000_000,
+        borrowed_amount(&position, &pool).unwrap(),
+        collateral_amount(&position, &pool).unwrap(),
+        &pool,
+        price,
+        &pool,
         price
-    )?;
+    ).unwrap();
+    assert_eq!(repay, 42_500_000);
+    assert_eq!(seize, 44_625_000);
 
-    require!(health_factor >= 100, LendingError::InsufficientCollateral);
+    apply_liquidation_repay(&mut position, &mut pool, repay).unwrap();
+    assert_eq!(apply_liquidation_seize(&mut position, &mut pool, seize).unwrap(), 44_625_000);
+    assert_eq!(position.scaled_borrowed_amount, 42_500_000);
+    assert_eq!(position.scaled_deposited_amount, 55_375_000);
+    assert_eq!(pool.total_borrowed, 457_500_000);
+    assert_eq!(pool.total_supply, 955_375_000);
+
+    // 健康的同池仓位不能被清算
+    let healthy = UserPosition {
+        scaled_deposited_amount: 100_000_000,
+        scaled_borrowed_amount: 50_000_000,
+        collateral_enabled: true,
+        ..UserPosition::default()
+    };
+    assert!(check_before_liquidate(&obligation, &[(position_key, &healthy, &pool, price)], &[]).is_err());
+}
+
+// 校验资金池风险参数
+pub fn validate_pool_config(pool: &LendingPool) -> Result<()> {
+    require!(pool.reserve_factor <= 100, LendingError::InvalidPoolConfig);
+    // 最高借款比例不高于清算阈值，刚借满的仓位不会立即被清算
+    require!(
+        pool.loan_to_value <= pool.liquidation_threshold && pool.liquidation_threshold <= 100,
+        LendingError::InvalidPoolConfig
+    );
+    require!(
+        pool.close_factor > 0 && pool.close_factor <= 100,
+        LendingError::InvalidPoolConfig
+    );
+    require!(pool.max_staleness > 0, LendingError::InvalidPoolConfig);
+    require!(pool.flash_loan_fee_bps <= 10_000, LendingError::InvalidPoolConfig);
+    require!(pool.insurance_fund_factor <= 100, LendingError::InvalidPoolConfig);
+    // 固定价格模式必须给出有效价格
+    if pool.oracle_type == OracleType::Fixed {
+        require!(pool.oracle_fixed_price > 0, LendingError::InvalidPoolConfig);
+    }
+
+    validate_rate_model(&pool.rate_model)?;
+
+    // 刚达到清算阈值的仓位被清算时，没收的抵押品不能超过其全部抵押品
+    let bonus = Decimal::one().try_add(Decimal::from_percent(pool.liquidation_bonus))?;
+    let max_seize = Decimal::from_percent(pool.liquidation_threshold).try_mul(bonus)?;
+    require!(max_seize <= Decimal::one(), LendingError::InvalidPoolConfig);
 
     Ok(())
 }
 
-fn calculate_interest_rate(pool: &LendingPool) -> u64 {
-    let utilization_rate = (pool.total_borrowed * 100) / pool.total_supply;
+#[test]
+fn test_validate_pool_config() {
+    let mut pool = LendingPool {
+        reserve_factor: 10,
+        loan_to_value: 75,
+        liquidation_threshold: 80,
+        liquidation_bonus: 5,
+        close_factor: 50,
+        max_staleness: 60,
+        rate_model: InterestRateModel {
+            optimal_utilization_bps: 8_000,
+            base_rate_bps: 200,
+            slope1_bps: 800,
+            slope2_bps: 10_000,
+            max_rate_bps: 10_200,
+            ..InterestRateModel::default()
+        },
+        ..LendingPool::default()
+    };
+    assert!(validate_pool_config(&pool).is_ok());
+
+    // 80% * 1.3 > 100%
+    pool.liquidation_bonus = 30;
+    assert!(validate_pool_config(&pool).is_err());
 
-    let base_rate = pool.base_rate;
-    let slope = match utilization_rate {
-        0..=50 => 10, // 0-50%: 斜率 0.1%
-        51..=80 => 20, // 50-80%: 斜率 0.2%
-        _ => 30, // >80%: 斜率 0.3%
+    // 最高借款比例不能高于清算阈值
+    pool.liquidation_bonus = 5;
+    pool.loan_to_value = 85;
+    assert!(validate_pool_config(&pool).is_err());
+
+    pool.loan_to_value = 75;
+    pool.close_factor = 0;
+    assert!(validate_pool_config(&pool).is_err());
+
+    // 固定价格模式缺少价格
+    pool.close_factor = 50;
+    pool.oracle_type = OracleType::Fixed;
+    assert!(validate_pool_config(&pool).is_err());
+    pool.oracle_fixed_price = 1_000_000_000_000;
+    assert!(validate_pool_config(&pool).is_ok());
+}
+
+// 闪电贷手续费：金额 * flash_loan_fee_bps，向上取整
+pub fn flash_loan_fee(pool: &LendingPool, amount: u64) -> Result<u64> {
+    Decimal::from(amount)
+        .try_mul(Decimal::from_bps(pool.flash_loan_fee_bps as u64))?
+        .try_round_up_u64()
+}
+
+// 将一笔手续费计入储备：按储备金率计入协议手续费，其余按比例放大流动性指数分给存款人
+// 无存款时全部计入协议手续费
+pub fn distribute_fee(pool: &mut LendingPool, fee: u64) -> Result<()> {
+    let supply_fee = if pool.total_supply == 0 {
+        0
+    } else {
+        let depositor_share = Decimal::from_percent(100u8.saturating_sub(pool.reserve_factor));
+        Decimal::from(fee).try_mul(depositor_share)?.try_floor_u64()?
     };
 
-    base_rate + utilization_rate * slope
+    if supply_fee > 0 {
+        // liquidity_index *= (总供应 + 存款人收益) / 总供应
+        let growth = Decimal::from(pool.total_supply)
+            .try_add(Decimal::from(supply_fee))?
+            .try_div(Decimal::from(pool.total_supply))?;
+        pool.liquidity_index = Decimal::from_scaled_val(pool.liquidity_index)
+            .try_mul(growth)?
+            .to_scaled_val()?;
+        pool.total_supply = pool.total_supply
+            .checked_add(supply_fee)
+            .ok_or(LendingError::MathOverflow)?;
+    }
+
+    pool.accumulated_protocol_fees = pool.accumulated_protocol_fees
+        .checked_add(fee - supply_fee)
+        .ok_or(LendingError::MathOverflow)?;
+    Ok(())
+}
+
+// 提取的协议手续费中转入保险基金的部分（向下取整）
+pub fn insurance_fund_share(pool: &LendingPool, amount: u64) -> Result<u64> {
+    Decimal::from(amount)
+        .try_mul(Decimal::from_percent(pool.insurance_fund_factor))?
+        .try_floor_u64()
+}
+
+// 由存款人分摊损失：按比例降低流动性指数并扣减总供应，返回实际分摊的金额
+// 损失不超过总供应；存款被全部抵消时保留原指数，避免之后的存款除零
+pub fn socialize_loss(pool: &mut LendingPool, loss: u64) -> Result<u64> {
+    let loss = loss.min(pool.total_supply);
+    if loss == 0 {
+        return Ok(0);
+    }
+
+    let remaining_supply = pool.total_supply - loss;
+    if remaining_supply > 0 {
+        // liquidity_index *= (总供应 - 损失) / 总供应，向下取整
+        let shrink = Decimal::from(remaining_supply).try_div(Decimal::from(pool.total_supply))?;
+        pool.liquidity_index = Decimal::from_scaled_val(pool.liquidity_index)
+            .try_mul(shrink)?
+            .to_scaled_val()?;
+    }
+    pool.total_supply = remaining_supply;
+
+    Ok(loss)
 }
 
 #[test]
-fn test_calculate_interest_rate() {
-    let pool = LendingPool {
-        total_supply: 100_000,
-        total_borrowed: 50_000,
-        base_rate: 500, // 5%
+fn test_insurance_and_socialize_loss() {
+    let mut pool = LendingPool {
+        total_supply: 1_000_000,
+        liquidity_index: Rate::from_bps(11_000).to_scaled_val(), // 1.1
+        insurance_fund_factor: 20,
         ..LendingPool::default()
     };
 
-    // 资金利用率 = 50%
-    let rate = calculate_interest_rate(&pool);
-    assert_eq!(rate, 500 + 50 * 10); // 5% + (50 * 0.2%) = 15%
+    assert_eq!(insurance_fund_share(&pool, 1_001).unwrap(), 200);
+
+    // 损失 10%：指数 1.1 -> 0.99
+    assert_eq!(socialize_loss(&mut pool, 100_000).unwrap(), 100_000);
+    assert_eq!(pool.total_supply, 900_000);
+    assert_eq!(pool.liquidity_index, Rate::from_bps(9_900).to_scaled_val());
+
+    // 损失超过总供应时只分摊现有供应，指数保持不变
+    assert_eq!(socialize_loss(&mut pool, 2_000_000).unwrap(), 900_000);
+    assert_eq!(pool.total_supply, 0);
+    assert_eq!(pool.liquidity_index, Rate::from_bps(9_900).to_scaled_val());
+    assert_eq!(socialize_loss(&mut pool, 1).unwrap(), 0);
 }
 
-// 更新全局利息
-pub fn accrue_interest(pool: &mut Account<LendingPool>) -> Result<()> {
-    let current_time = Clock::get()?.unix_timestamp;
+#[test]
+fn test_flash_loan_fee() {
+    let mut pool = LendingPool {
+        total_supply: 1_000_000,
+        liquidity_index: Rate::one().to_scaled_val(),
+        reserve_factor: 10,
+        flash_loan_fee_bps: 9, // 0.09%
+        ..LendingPool::default()
+    };
+
+    // 手续费向上取整
+    assert_eq!(flash_loan_fee(&pool, 1_000_000).unwrap(), 900);
+    assert_eq!(flash_loan_fee(&pool, 1).unwrap(), 1);
+
+    // 90% 归存款人，10% 归协议
+    distribute_fee(&mut pool, 1_000).unwrap();
+    assert_eq!(pool.total_supply, 1_000_900);
+    assert_eq!(pool.accumulated_protocol_fees, 100);
+    assert_eq!(pool.liquidity_index, Rate::from_bps(10_009).to_scaled_val());
+
+    // 无存款时全部计入协议手续费
+    pool.total_supply = 0;
+    distribute_fee(&mut pool, 50).unwrap();
+    assert_eq!(pool.accumulated_protocol_fees, 150);
+}
+
+// FlashRepay 账户列表中 pool 的位置（user, user_token_account, pool, ...）
+const FLASH_REPAY_POOL_INDEX: usize = 2;
+
+#[test]
+fn test_flash_repay_pool_index() {
+    use anchor_lang::ToAccountMetas;
+
+    // 按 Anchor 生成的账户顺序校验（event-cpi 账户追加在末尾，不影响 pool 的位置）
+    let pool = Pubkey::new_unique();
+    let accounts = crate::accounts::FlashRepay {
+        user: Pubkey::new_unique(),
+        user_token_account: Pubkey::new_unique(),
+        pool,
+        pool_token_account: Pubkey::new_unique(),
+        token_program: Pubkey::new_unique(),
+        #[cfg(feature = "event-cpi")]
+        event_authority: Pubkey::new_unique(),
+        #[cfg(feature = "event-cpi")]
+        program: Pubkey::new_unique(),
+    };
+    assert_eq!(accounts.to_account_metas(None)[FLASH_REPAY_POOL_INDEX].pubkey, pool);
+}
+
+// 闪电贷还款检查：flash_borrow 必须是本程序的顶层指令（禁止 CPI 调用），
+// 且同一交易的后续指令中存在针对同一资金池、金额一致的 flash_repay
+pub fn check_flash_repay(instructions: &AccountInfo, pool_key: Pubkey, amount: u64) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)? as usize;
+    let current = load_instruction_at_checked(current_index, instructions)?;
+    require_keys_eq!(current.program_id, crate::ID, LendingError::FlashLoanCpiNotAllowed);
+
+    let mut index = current_index + 1;
+    // 越过最后一条指令时返回错误，结束查找
+    while let Ok(ix) = load_instruction_at_checked(index, instructions) {
+        if
+            ix.program_id == crate::ID &&
+            ix.data.len() >= 16 &&
+            ix.data[..8] == crate::instruction::FlashRepay::DISCRIMINATOR
+        {
+            let repay_amount = u64::from_le_bytes(ix.data[8..16].try_into().unwrap());
+            // 只比较 pool 所在位置，资金池地址出现在其他账户位置时不算数
+            let repay_pool = ix.accounts.get(FLASH_REPAY_POOL_INDEX).map(|meta| meta.pubkey);
+            if repay_amount == amount && repay_pool == Some(pool_key) {
+                return Ok(());
+            }
+        }
+        index += 1;
+    }
+
+    err!(LendingError::FlashRepayMissing)
+}
+
+#[test]
+fn test_check_flash_repay() {
+    use anchor_lang::solana_program::sysvar::instructions::{
+        construct_instructions_data,
+        store_current_index,
+        BorrowedAccountMeta,
+        BorrowedInstruction,
+    };
+
+    let pool_key = Pubkey::new_unique();
+    let other_program = Pubkey::new_unique();
+    let borrow_data = crate::instruction::FlashBorrow::DISCRIMINATOR.to_vec();
+    let repay_data = |amount: u64| {
+        let mut data = crate::instruction::FlashRepay::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        data
+    };
+    let user = Pubkey::new_unique();
+    let other_pool = Pubkey::new_unique();
+    let meta = |pubkey| BorrowedAccountMeta { pubkey, is_signer: false, is_writable: true };
+    let pool_meta = || vec![meta(&pool_key)];
+    // 按 FlashRepay 的账户顺序构造（user, user_token_account, pool），user_token_account 位置可任意填写
+    let repay_metas = |user_token_account, pool| vec![meta(&user), meta(user_token_account), meta(pool)];
+
+    // 构造指令系统变量账户并检查第一条指令
+    let check = |instructions: Vec<BorrowedInstruction>, amount: u64| {
+        let mut data = construct_instructions_data(&instructions);
+        store_current_index(&mut data, 0);
+        let mut lamports = 0;
+        let key = anchor_lang::solana_program::sysvar::instructions::ID;
+        let owner = anchor_lang::solana_program::sysvar::ID;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        check_flash_repay(&info, pool_key, amount).is_ok()
+    };
+
+    let matching_repay = repay_data(1_000);
+    let borrow = || BorrowedInstruction { program_id: &crate::ID, accounts: pool_meta(), data: &borrow_data };
+
+    // 后续存在金额一致的还款指令
+    let repay = BorrowedInstruction { program_id: &crate::ID, accounts: repay_metas(&pool_key, &pool_key), data: &matching_repay };
+    assert!(check(vec![borrow(), repay], 1_000));
+
+    // 缺少还款指令或金额不一致
+    assert!(!check(vec![borrow()], 1_000));
+    let repay = BorrowedInstruction { program_id: &crate::ID, accounts: repay_metas(&pool_key, &pool_key), data: &matching_repay };
+    assert!(!check(vec![borrow(), repay], 2_000));
+
+    // 当前顶层指令不属于本程序（经由 CPI 调用）
+    let outer = BorrowedInstruction { program_id: &other_program, accounts: pool_meta(), data: &borrow_data };
+    let repay = BorrowedInstruction { program_id: &crate::ID, accounts: repay_metas(&pool_key, &pool_key), data: &matching_repay };
+    assert!(!check(vec![outer, repay], 1_000));
+
+    // 资金池地址只出现在非 pool 位置（还款实际归还到其他资金池）
+    let repay = BorrowedInstruction { program_id: &crate::ID, accounts: repay_metas(&pool_key, &other_pool), data: &matching_repay };
+    assert!(!check(vec![borrow(), repay], 1_000));
+}
+
+// 计算清算金额，返回 (实际偿还金额, 没收的抵押品数量)
+// - 单次偿还不超过债务 * close_factor（借款资金池）
+// - 没收价值 = 偿还价值 * (1 + liquidation_bonus)（抵押资金池），没收数量向下取整
+// - 抵押品不足时按可没收数量反推偿还金额，向上取整
+pub fn calculate_liquidation_amounts(
+    repay_amount: u64,
+    debt: u64,
+    collateral: u64,
+    repay_pool: &LendingPool,
+    repay_price: Decimal,
+    seize_pool: &LendingPool,
+    seize_price: Decimal
+) -> Result<(u64, u64)> {
+    require!(!repay_price.is_zero() && !seize_price.is_zero(), LendingError::InvalidOraclePrice);
+
+    let max_repay = Decimal::from(debt)
+        .try_mul(Decimal::from_percent(repay_pool.close_factor))?
+        .try_floor_u64()?;
+    let repay_amount = repay_amount.min(max_repay);
+
+    let repay_unit = token_unit(repay_pool.decimals)?;
+    let seize_unit = token_unit(seize_pool.decimals)?;
+    let bonus = Decimal::one().try_add(Decimal::from_percent(seize_pool.liquidation_bonus))?;
+
+    // 偿还价值 * (1 + 奖励) 折算为抵押品数量
+    let seize_amount = Decimal::from(repay_amount)
+        .try_mul(repay_price)?
+        .try_div(repay_unit)?
+        .try_mul(bonus)?
+        .try_mul(seize_unit)?
+        .try_div(seize_price)?
+        .try_floor_u64()?;
+
+    if seize_amount <= collateral {
+        return Ok((repay_amount, seize_amount));
+    }
+
+    // 抵押品不足：没收全部抵押品，偿还金额按比例反推
+    let required_repay = Decimal::from(collateral)
+        .try_mul_round(seize_price, Rounding::Up)?
+        .try_div_round(seize_unit, Rounding::Up)?
+        .try_div_round(bonus, Rounding::Up)?
+        .try_mul_round(repay_unit, Rounding::Up)?
+        .try_div_round(repay_price, Rounding::Up)?
+        .try_round_up_u64()?;
+
+    Ok((required_repay.min(repay_amount), collateral))
+}
+
+#[test]
+fn test_calculate_liquidation_amounts() {
+    let repay_pool = LendingPool {
+        decimals: 6,
+        close_factor: 50,
+        ..LendingPool::default()
+    };
+    let seize_pool = LendingPool {
+        decimals: 9,
+        liquidation_bonus: 5,
+        ..LendingPool::default()
+    };
+
+    // 债务 1000 USDC，最多偿还 50%；SOL 价格 100，USDC 价格 1
+    let (repay, seize) = calculate_liquidation_amounts(
+        1_000_000_000,
+        1_000_000_000,
+        100_000_000_000,
+        &repay_pool,
+        Decimal::one(),
+        &seize_pool,
+        Decimal::from(100u64)
+    ).unwrap();
+    assert_eq!(repay, 500_000_000);
+    // 500 USDC * 1.05 / 100 = 5.25 SOL
+    assert_eq!(seize, 5_250_000_000);
+
+    // 抵押品仅剩 1.05 SOL 时，按比例只偿还 100 USDC
+    let (repay, seize) = calculate_liquidation_amounts(
+        1_000_000_000,
+        1_000_000_000,
+        1_050_000_000,
+        &repay_pool,
+        Decimal::one(),
+        &seize_pool,
+        Decimal::from(100u64)
+    ).unwrap();
+    assert_eq!(repay, 100_000_000);
+    assert_eq!(seize, 1_050_000_000);
+}
+
+// 按经过的时间结算利息（纯计算，便于测试）
+// - 借款指数按借款利率逐秒复利增长，新增利息计入总借款
+// - 流动性指数按存款利率（已扣除储备金率）增长，存款人收益计入总供应
+// - 两者之差（储备金份额及复利差额）计入协议手续费
+// 返回 (新增借款利息, 其中存款人收益)
+pub fn accrue_pool_interest(pool: &mut LendingPool, current_time: i64) -> Result<(u64, u64)> {
     let time_elapsed = current_time - pool.last_update_time;
+    if time_elapsed <= 0 {
+        return Ok((0, 0));
+    }
+
+    let mut accrued = (0, 0);
+    if pool.total_borrowed > 0 {
+        // 1. 计算当前年化借款/存款利率
+        let borrow_rate = calculate_borrow_rate(pool)?;
+        let supply_rate = calculate_supply_rate(pool)?;
+
+        // 2. 计算增长系数
+        let borrow_factor = compound_interest_factor(borrow_rate, time_elapsed as u64)?;
+        let supply_factor = linear_interest_factor(supply_rate, time_elapsed as u64)?;
 
-    if time_elapsed > 0 && pool.total_borrowed > 0 {
-        // 计算当前利率
-        let borrow_rate = calculate_interest_rate(pool);
+        // 3. 借款利息向上取整，存款人收益向下取整
+        let interest = Decimal::from(pool.total_borrowed)
+            .try_mul_round(borrow_factor.try_sub(Rate::one())?.into(), Rounding::Up)?
+            .try_round_up_u64()?;
+        let supply_interest = Decimal::from(pool.total_supply)
+            .try_mul(supply_factor.try_sub(Rate::one())?)?
+            .try_floor_u64()?
+            .min(interest);
+        let protocol_fee = interest - supply_interest;
 
-        // 计算利息
-        let interest =
-            pool.total_borrowed
-                .checked_mul(borrow_rate)
-                .and_then(|v| v.checked_mul(time_elapsed as u64))
-                .ok_or(LendingError::MathOverflow)? /
-            (365 * 24 * 60 * 60); // 年化转秒级
+        // 4. 按系数放大指数，使每个份额分得的利息精确一致
+        // 流动性指数按实际计入的存款人收益放大（收益被截断时同样适用），保证 份额 * 指数 不超过总供应
+        pool.borrow_index = Rate::from_scaled_val(pool.borrow_index)
+            .try_mul_round(borrow_factor, Rounding::Up)?
+            .to_scaled_val();
+        grow_liquidity_index(pool, supply_interest)?;
 
-        // 更新总借款（复利）
+        // 5. 更新总借款、总供应与累计协议手续费
         pool.total_borrowed = pool.total_borrowed
             .checked_add(interest)
             .ok_or(LendingError::MathOverflow)?;
-
-        // 更新流动性指数和借款指数
-        pool.liquidity_index = pool.liquidity_index
-            .checked_add(((interest * 1_000_000_000) / pool.total_supply).into())
+        pool.total_supply = pool.total_supply
+            .checked_add(supply_interest)
             .ok_or(LendingError::MathOverflow)?;
-
-        pool.borrow_index = pool.borrow_index
-            .checked_add(((interest * 1_000_000_000) / pool.total_borrowed).into())
+        pool.accumulated_protocol_fees = pool.accumulated_protocol_fees
+            .checked_add(protocol_fee)
             .ok_or(LendingError::MathOverflow)?;
+        accrued = (interest, supply_interest);
+    }
+
+    // 无借款期间同样推进时间，避免之后的借款被追溯计息
+    pool.last_update_time = current_time;
+    Ok(accrued)
+}
 
-        // 更新最后更新时间
-        pool.last_update_time = current_time;
+// 流动性指数 *= 1 + supply_interest / total_supply（向下取整），需在更新总供应之前调用
+fn grow_liquidity_index(pool: &mut LendingPool, supply_interest: u64) -> Result<()> {
+    if supply_interest == 0 {
+        return Ok(());
     }
+    let growth = Rate::from_scaled_val(supply_interest as u128).try_div(Rate::from_scaled_val(pool.total_supply as u128))?;
+    pool.liquidity_index = Rate::from_scaled_val(pool.liquidity_index)
+        .try_mul(Rate::one().try_add(growth)?)?
+        .to_scaled_val();
     Ok(())
 }
 
+#[test]
+fn test_grow_liquidity_index_clamped() {
+    // 按利率应得 10 的收益被截断为 4（借款利息不足）时，指数只按 4 增长
+    let mut pool = LendingPool { total_supply: 1_000, liquidity_index: Rate::one().to_scaled_val(), ..LendingPool::default() };
+    let shares = pool.total_supply;
+    grow_liquidity_index(&mut pool, 4).unwrap();
+    pool.total_supply += 4;
+    assert_eq!(pool.liquidity_index, Rate::from_bps(10_040).to_scaled_val());
+    assert_eq!(scaled_to_amount(shares, pool.liquidity_index, Rounding::Down).unwrap(), pool.total_supply);
+
+    // 无法整除时向下取整，份额 * 指数 不超过总供应
+    let mut pool = LendingPool { total_supply: 3, liquidity_index: Rate::one().to_scaled_val(), ..LendingPool::default() };
+    grow_liquidity_index(&mut pool, 1).unwrap();
+    pool.total_supply += 1;
+    assert!(pool.liquidity_index < Rate::from_bps(13_334).to_scaled_val());
+    assert!(scaled_to_amount(3, pool.liquidity_index, Rounding::Up).unwrap() <= pool.total_supply);
+}
+
+#[test]
+fn test_accrue_pool_interest() {
+    let mut pool = LendingPool {
+        total_supply: 1_000_000_000,
+        total_borrowed: 500_000_000,
+        liquidity_index: Rate::one().to_scaled_val(),
+        borrow_index: Rate::one().to_scaled_val(),
+        reserve_factor: 10,
+        rate_model: InterestRateModel {
+            optimal_utilization_bps: 8_000,
+            base_rate_bps: 1_000, // 恒定 10%
+            max_rate_bps: 1_000,
+            ..InterestRateModel::default()
+        },
+        ..LendingPool::default()
+    };
+
+    // 一年后：借款按复利增长约 10.517%，存款人获得 10% * 50% * 90% = 4.5%
+    let year = 365 * 24 * 60 * 60;
+    let (accrued_interest, supply_interest) = accrue_pool_interest(&mut pool, year).unwrap();
+    assert_eq!(pool.last_update_time, year);
+    assert!(pool.borrow_index > Rate::from_bps(11_051).to_scaled_val());
+    assert_eq!(pool.liquidity_index, Rate::from_bps(10_450).to_scaled_val());
+    assert_eq!(pool.total_supply, 1_045_000_000);
+
+    // 总借款增量 = 存款人收益 + 协议手续费
+    let interest = pool.total_borrowed - 500_000_000;
+    assert_eq!(interest, accrued_interest);
+    assert_eq!(supply_interest, 45_000_000);
+    assert_eq!(interest, 45_000_000 + pool.accumulated_protocol_fees);
+    assert!(pool.accumulated_protocol_fees >= 5_000_000);
+
+    // 无借款时只推进时间
+    pool.total_borrowed = 0;
+    let borrow_index = pool.borrow_index;
+    assert_eq!(accrue_pool_interest(&mut pool, year * 2).unwrap(), (0, 0));
+    assert_eq!(pool.borrow_index, borrow_index);
+    assert_eq!(pool.last_update_time, year * 2);
+}
+
+// 资金池当前状态快照（指数、利用率与利率），随事件记录
+pub fn pool_state_snapshot(pool: &LendingPool) -> Result<PoolStateSnapshot> {
+    Ok(PoolStateSnapshot {
+        total_supply: pool.total_supply,
+        total_borrowed: pool.total_borrowed,
+        liquidity_index: pool.liquidity_index,
+        borrow_index: pool.borrow_index,
+        utilization_rate: utilization_rate(pool)?.to_scaled_val(),
+        borrow_rate: calculate_borrow_rate(pool)?.to_scaled_val(),
+        supply_rate: calculate_supply_rate(pool)?.to_scaled_val(),
+        accumulated_protocol_fees: pool.accumulated_protocol_fees,
+    })
+}
+
+// 更新全局利息，产生新利息时返回待记录的事件（由指令通过 emit_event! 记录）
+pub fn accrue_interest(pool: &mut Account<LendingPool>) -> Result<Option<InterestAccruedEvent>> {
+    let current_time = Clock::get()?.unix_timestamp;
+    let elapsed = current_time - pool.last_update_time;
+    let (interest, supply_interest) = accrue_pool_interest(pool, current_time)?;
+    if interest == 0 {
+        return Ok(None);
+    }
+
+    Ok(
+        Some(InterestAccruedEvent {
+            pool: pool.key(),
+            elapsed,
+            interest,
+            supply_interest,
+            protocol_fee: interest - supply_interest,
+            state: pool_state_snapshot(pool)?,
+            timestamp: current_time,
+        })
+    )
+}
+
+// 记录事件：启用 event-cpi 特性时通过自调用 CPI（emit_cpi!）写入内部指令，
+// 避免深层 CPI 或日志溢出时被截断；否则写入程序日志（emit!）
+#[macro_export]
+macro_rules! emit_event {
+    ($ctx:ident, $event:expr) => {
+        #[cfg(feature = "event-cpi")]
+        {
+            // 先构造事件，结束事件字段对账户的借用
+            let event = $event;
+            let ctx = &$ctx;
+            anchor_lang::prelude::emit_cpi!(event);
+        }
+        #[cfg(not(feature = "event-cpi"))]
+        anchor_lang::prelude::emit!($event);
+    };
+}
+
 #[macro_export]
 macro_rules! err {
     ($error:tt $(,)?) => {
@@ -12,10 +12,13 @@ describe("Initialize Pool", () => {
 const CHAINLINK_PROGRAM = "HEvSKofvBgfaexv23kMabbYqxasxU3mQ4ibBMEmJWHny";
 const CHAINLINK_SOL_USD = "99B2bTijsU6f1GCT73HmdR7HCFFjGMBcPZY6jZ96ynrR";
 const CHAINLINK_USDC_USD = "2EmfL3MqL3YHABudGNmajjCpR13NNEn9Y4LWxbDm6SwR";
+const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 
   it("Initialize Pool", async () => {
     // 代币 Mint 地址
     const mint = new anchor.web3.PublicKey("..."); // 替换为实际的 Mint 地址
+    // 协议手续费接收账户（同一 Mint 的代币账户）
+    const treasury = new anchor.web3.PublicKey("..."); // 替换为实际的金库地址
 
     // 计算资金池 PDA
     const [poolPda, poolBump] = await anchor.web3.PublicKey.findProgramAddress(
@@ -23,24 +26,77 @@ const CHAINLINK_USDC_USD = "2EmfL3MqL3YHABudGNmajjCpR13NNEn9Y4LWxbDm6SwR";
       program.programId
     );
 
+    // 存款凭证 Mint 及其 Metaplex 元数据账户
+    const [receiptMint] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt_mint"), poolPda.toBuffer()],
+      program.programId
+    );
+    const [receiptMetadata] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), receiptMint.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+
+    // 保险基金代币账户
+    const [insuranceVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("insurance_vault"), poolPda.toBuffer()],
+      program.programId
+    );
+
+    // 仓位存款凭证托管账户
+    const [collateralVault] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("collateral_vault"), poolPda.toBuffer()],
+      program.programId
+    );
+
     // 调用初始化指令
-    await program.methods.initializePool(
-      mint,          // 代币 Mint 地址
-      6,             // 代币精度（如 USDC 是 6）
-      10,            // 储备金率（10%）
-      75,            // 抵押率（75%）
-      500            // 基础利率（5% APR）
-    )
+    // Mint 地址与精度由 mint 账户决定，不再作为参数传入
+    await program.methods.initializePool({
+      reserveFactor: 10,                // 储备金率（10%）
+      loanToValue: 75,                  // 最高借款比例（75%）
+      liquidationThreshold: 80,         // 清算阈值（80%）
+      rateModel: {                      // 利率模型（基点）
+        optimalUtilizationBps: 8000,    // 最优利用率 80%
+        baseRateBps: 200,               // 基础利率 2%
+        slope1Bps: 800,                 // 0-80%：2% -> 10%
+        slope2Bps: 10000,               // 80-100%：10% -> 110%
+        extraKinks: Array(3).fill({ utilizationBps: 0, rateBps: 0 }),
+        extraKinkCount: 0,
+        maxRateBps: 10200,              // 利率上限 102%
+      },
+      liquidationBonus: 5,              // 清算奖励（5%）
+      closeFactor: 50,                  // 平仓系数（50%）
+      maxStaleness: new anchor.BN(60),  // 报价最长有效期（60 秒）
+      oracleType: { chainlink: {} },    // 预言机类型
+      oracleFixedPrice: new anchor.BN(0),
+      treasury: treasury,               // 协议手续费接收账户
+      feeAuthority: provider.wallet.publicKey,
+      flashLoanFeeBps: 9,               // 闪电贷手续费（0.09%）
+      supplyCap: new anchor.BN(0),      // 总供应上限（0 表示不限）
+      borrowCap: new anchor.BN(0),      // 总借款上限（0 表示不限）
+      pauseGuardian: provider.wallet.publicKey, // 暂停守护者
+      insuranceFundFactor: 20,          // 协议手续费转入保险基金的比例（20%）
+      receiptName: "Lending Pool USDC",  // 存款凭证元数据
+      receiptSymbol: "lpUSDC",
+      receiptUri: "",
+    })
       .accounts({
         pool: poolPda,
         mint: mint,
+        receiptMint: receiptMint,
+        receiptMetadata: receiptMetadata,
+        insuranceVault: insuranceVault,
+        collateralVault: collateralVault,
         authority: provider.wallet.publicKey,
+        priceFeed: new anchor.web3.PublicKey(CHAINLINK_USDC_USD),
+        feedProgram: new anchor.web3.PublicKey(CHAINLINK_PROGRAM),
         tokenProgram: TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
         systemProgram: anchor.web3.SystemProgram.programId,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
       .rpc();
 
     console.log("Pool Initialized:", poolPda.toBase58());
+    console.log("Receipt Mint:", receiptMint.toBase58());
   });
 });
\ No newline at end of file